@@ -30,10 +30,41 @@ mod feature {
         pub name: String,
         pub pass: String,
         pub remember: bool,
+        pub retries: u32,
+        pub tab: usize,
+        pub tabs: Vec<String>,
+        pub quitting: bool,
     }
 
     pub fn demo_frame(style: &Style, ui: &mut Ui, state: &mut State) {
 
+        // a menu bar shows the top level items of a menu, their submenus open below them.
+        // pressing alt focuses the menu bar, so it can be used with the arrow keys.
+        let file = [
+            MenuItem::StringItem("new", "New", &[]),
+            MenuItem::StringItem("open", "Open", &[]),
+            MenuItem::Separator,
+            MenuItem::StringItem("quit", "Quit", &[]),
+        ];
+        let edit = [
+            MenuItem::StringItem("undo", "Undo", &[]),
+            MenuItem::StringItem("redo", "Redo", &[]),
+        ];
+        let menu = [
+            MenuItem::StringItem("file", "File", &file),
+            MenuItem::StringItem("edit", "Edit", &edit),
+        ];
+        ui.layer(style, "menu", Layer::Back).with(|ui| {
+            if let Some(&"quit") = ui.add("bar", MenuBar::new(&menu)).result {
+                state.quitting = true;
+            }
+            layout_rules!(ui,
+                (bar.left = super.left),
+                (bar.right = super.right),
+                (bar.top = super.top),
+            );
+        });
+
         // for the example we're going to make some kind of login window.
         // it consists of a name, password, remember me field and login/cancel buttons.
         // to show an example of some more stuff there is a scroll with a list of labels below it.
@@ -42,13 +73,17 @@ mod feature {
         // 1) `Layer::Back` layers will always remain on the background.
         // 2) `Layer::Normal` layers will be ordered where the last used layer is on top.
         // 3) `Layer::Modal` layers will always be in front of all the other layers.
+        // 4) `Layer::Overlay` layers are drawn on top of everything, but never receive input.
         // this is a normal layer.
         ui.layer(style, "test", Layer::Normal).with(|ui| {
             // a window is just a widget that can be dragged around in it's parent.
             ui.add("w", Window::new(Rect::from_wh(256.0, 256.0), true)).with(|ui| {
                 // make widgets for the login controls
                 ui.add("name_txt", Label::simple("Username: "));
-                ui.add("name", Input::new(&mut state.name));
+                ui.add("name", Input::new(&mut state.name)
+                    .with_placeholder("Player name")
+                    .with_max_length(16)
+                    .with_filter(|c| c.is_alphanumeric()));
                 ui.add("pass_txt", Label::simple("Password: "));
                 ui.add("pass", Input::password(&mut state.pass));
 
@@ -56,10 +91,17 @@ mod feature {
                 // So in this case the Login label lives inside the button.
                 // The wrap function also sets the right layout for the child so it is
                 //  aligned with the host margins.
-                ui.add("b1", Button::new().with_size((128.0, 32.0))).wrap(Label::simple("Login"));
-                ui.add("b2", Button::new().with_size((128.0, 32.0))).wrap(Label::simple("Cancel"));
+                // tooltips can be attached to any widget, they show up when the widget is hovered.
+                ui.add("b1", Button::new().with_size((128.0, 32.0)))
+                    .tooltip("Log in with the name and password above")
+                    .wrap(Label::simple("Login"));
+                if ui.add("b2", Button::new().with_size((128.0, 32.0))).wrap(Label::simple("Cancel")) {
+                    state.quitting = true;
+                }
                 state.remember = ui.add("cb", Toggle::checkbox(state.remember, true, false)).result.unwrap_or(state.remember);
                 ui.add("txt", Label::simple("Remember me"));
+                state.retries = ui.add("retries", NumberInput::new(state.retries).with_range(0, 10).with_drag(0.1))
+                    .result.unwrap_or(state.retries);
 
                 // The wrap_with functions does the same as wrap, but allow you to specify a closure
                 //  with which you can add children to the wrapped widget.
@@ -103,7 +145,45 @@ mod feature {
                     (list.bottom = super.margin_bottom - 20.0),
                 );
             });
+
+            // tabs show one page at a time. the tabs can be reordered by dragging them and
+            //  closed with their close button, the result tells which tab to move or remove.
+            ui.add("settings", Window::new(Rect::from_xywh(400.0, 100.0, 256.0, 192.0), true).with_title("Settings").collapsible()).with(|ui| {
+                let result = {
+                    let tabs: Vec<Tab> = state.tabs.iter().map(|label| Tab::new(label).closable()).collect();
+                    ui.add("tabs", Tabs::new()).pages(&tabs, state.tab, |ui, index| {
+                        ui.add("text", Label::simple_owned(format!("Page {}", index + 1)));
+                        layout_rules!(ui,
+                            (text.left = super.left + 8.0),
+                            (text.top = super.top + 8.0),
+                        );
+                    })
+                };
+
+                if let Some(index) = result.closed {
+                    state.tabs.remove(index);
+                }
+                if let Some((from, to)) = result.moved {
+                    let tab = state.tabs.remove(from);
+                    state.tabs.insert(to, tab);
+                }
+                state.tab = result.selected;
+
+                layout_rules!(ui,
+                    (tabs.left = super.margin_left),
+                    (tabs.right = super.margin_right),
+                    (tabs.top = super.margin_top),
+                    (tabs.bottom = super.margin_bottom),
+                );
+            });
         });
+
+        // dialogs are shown in a modal layer until they return a result.
+        if state.quitting {
+            if ui.confirm(style, "quit", "Quit", "Are you sure you want to quit?") != DialogResult::Open {
+                state.quitting = false;
+            }
+        }
     }
 
     // boilerplate code for winit, glutin and gfx.
@@ -145,6 +225,10 @@ mod feature {
             name: "".to_string(),
             pass: "".to_string(),
             remember: false,
+            retries: 3,
+            tab: 0,
+            tabs: vec!["General".to_string(), "Notes".to_string(), "Log".to_string()],
+            quitting: false,
         };
 
         while !finished {
@@ -193,13 +277,25 @@ mod feature {
 
             // render ui
             encoder.clear(&main_color, [0.3, 0.3, 0.3, 1.0]);
-            let (drawlist, mouse_style, _mouse_mode) = ui.render();
+            let (drawlist, mouse_style, mouse_mode) = ui.render();
             ui_render.draw(1.0, &mut factory, &mut encoder, &main_color, &drawlist);
 
             // flush and swap
             encoder.flush(&mut device);
             window.swap_buffers().unwrap();
             window.set_cursor(convert_mouse_style(mouse_style));
+            if let Some(caret) = ui.caret_rect() {
+                // place the candidate window of the input method below the caret
+                window.set_ime_spot((caret.left as f64, caret.bottom as f64).into());
+            }
+            match mouse_mode {
+                // keep the cursor in place while a widget is dragging it
+                MouseMode::Locked(x, y) => {
+                    window.hide_cursor(true);
+                    window.set_cursor_position((x as f64, y as f64).into()).ok();
+                },
+                _ => window.hide_cursor(false),
+            }
             device.cleanup();
         }
     }
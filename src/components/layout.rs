@@ -1,9 +1,13 @@
 use super::*;
 
+// How an item of a linear layout takes part in the free space
 #[derive(Clone, PartialEq)]
 pub enum Constraint {
+    // Keeps the size of it's content
     Fixed,
+    // Grows with the free space, but not below the size of it's content
     Grow,
+    // Is sized by it's share of the free space only
     Fill,
 }
 
@@ -78,17 +82,23 @@ impl Layout {
     }
 
     pub fn as_editable(mut self, solver: &mut cassowary::Solver) -> Self {
-        solver.add_edit_variable(self.left, WEAK).expect("unexpected edit variable error");
-        solver.add_edit_variable(self.top, WEAK).expect("unexpected edit variable error");
+        self = self.as_movable(solver);
         solver.add_edit_variable(self.width, STRONG).expect("unexpected edit variable error");
         solver.add_edit_variable(self.height, STRONG).expect("unexpected edit variable error");
-        self.edits.push(self.left);
-        self.edits.push(self.top);
         self.edits.push(self.width);
         self.edits.push(self.height);
         self
     }
 
+    // Like `as_editable`, but the size is left to the constraints
+    pub fn as_movable(mut self, solver: &mut cassowary::Solver) -> Self {
+        solver.add_edit_variable(self.left, WEAK).expect("unexpected edit variable error");
+        solver.add_edit_variable(self.top, WEAK).expect("unexpected edit variable error");
+        self.edits.push(self.left);
+        self.edits.push(self.top);
+        self
+    }
+
     pub fn with_edit(mut self, edit: cassowary::Variable, solver: &mut cassowary::Solver) -> Self {
         solver.add_edit_variable(edit, STRONG).expect("unexpected edit variable error");
         self.edits.push(edit);
@@ -112,6 +122,13 @@ impl Layout {
         self
     }
 
+    // Let the content extend past the bottom margin by the value of an edit variable, so the
+    //  layout can be made smaller than it's content. Must be used after `with_margins`.
+    pub fn with_bottom_overflow(mut self, overflow: cassowary::Variable, solver: &mut cassowary::Solver) -> Self {
+        self.constraints[7] = self.margin_bottom |EQ(REQUIRED)| self.bottom - self.margin.bottom as f64 + overflow;
+        self.with_edit(overflow, solver)
+    }
+
     pub fn with_intrinsic_size_constraints(mut self, width: f32, height: f32, hugging: f64) -> Self {
         // compression resistance
         self.constraints.push(self.width |GE(STRONG)| width);
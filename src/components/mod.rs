@@ -6,12 +6,14 @@ pub mod background;
 pub mod clickable;
 pub mod clipper;
 pub mod drawing;
+pub mod overlay;
 
 pub use self::layout::*;
 pub use self::background::*;
 pub use self::clickable::*;
 pub use self::clipper::*;
 pub use self::drawing::*;
+pub use self::overlay::*;
 
 pub type Container<T> = Rc<RefCell<Vec<(Option<T>, usize)>>>;
 
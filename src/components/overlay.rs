@@ -0,0 +1,17 @@
+use super::*;
+
+// Primitives in an overlay are drawn after all layers have been drawn.
+// They are not clipped by the widget or any of it's parents, which makes them suitable for 
+//  popups that should escape their parent.
+#[derive(Clone)]
+pub struct Overlay {
+    pub primitives: Vec<Primitive>,
+}
+
+impl Overlay {
+    pub fn new() -> Self {
+        Self {
+            primitives: vec![],
+        }
+    }
+}
@@ -110,4 +110,11 @@ impl Tree {
             })
         }        
     }
+
+    // Check if the id is used anywhere in this tree or it's subtrees
+    pub fn contains(&self, id: Id) -> bool {
+        self.ids.values().any(|item| {
+            item.id == id || item.subs.as_ref().map_or(false, |sub| sub.contains(id))
+        })
+    }
 }
\ No newline at end of file
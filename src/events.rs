@@ -19,8 +19,9 @@ pub enum Key {
 
     Tab, Shift, Ctrl, 
     Alt, Space, Enter,
-    Backspace, Escape,
+    Backspace, Delete, Escape,
     Home, End,
+    PageUp, PageDown,
 
     Left, Right, Up, Down,
 }
@@ -41,6 +42,11 @@ impl Modifiers {
     pub fn logo() -> Modifiers { Modifiers{ ctrl: false, alt: false, shift: false, logo: true } }
 }
 
+/// The text of an input method event. Events stay `Copy`, so the text is kept by the `Ui`,
+///  it's stored with `Ui::composition_text()` and read with `Ui::composed()`.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub struct CompositionText(pub(crate) usize);
+
 #[derive(Clone,Copy,Debug)]
 pub enum Event {
     /// A button on some input device was pressed.
@@ -57,6 +63,12 @@ pub enum Event {
     Scroll(f32, f32),
     /// Text input was received, usually via the keyboard.
     Text(char),
+    /// An input method is composing text. The composition is shown at the caret until it is
+    ///  committed. The range is the cursor or the selected clause within the composition, in 
+    ///  characters. An empty composition ends composing.
+    Preedit(CompositionText, Option<(usize, usize)>),
+    /// An input method committed composed text.
+    Commit(CompositionText),
     /// The window was focused or lost focus.
     Focus(bool),
     /// The application exited it's main event loop
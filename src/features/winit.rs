@@ -156,6 +156,7 @@ fn convert_key(key: winit::VirtualKeyCode) -> Option<Key> {
         Vk::Space => Some(Key::Space), 
         Vk::Return => Some(Key::Enter),
         Vk::Back => Some(Key::Backspace),
+        Vk::Delete => Some(Key::Delete),
         Vk::Escape => Some(Key::Escape),
         Vk::Left => Some(Key::Left), 
         Vk::Right => Some(Key::Right), 
@@ -163,6 +164,8 @@ fn convert_key(key: winit::VirtualKeyCode) -> Option<Key> {
         Vk::Down => Some(Key::Down),
         Vk::Home => Some(Key::Home),
         Vk::End => Some(Key::End),
+        Vk::PageUp => Some(Key::PageUp),
+        Vk::PageDown => Some(Key::PageDown),
         _ => None,
     }
 }
\ No newline at end of file
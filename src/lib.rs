@@ -20,6 +20,7 @@ use std::ops::Deref;
 use std::ops::DerefMut;
 use std::any::Any;
 use std::any::TypeId;
+use std::time::{Duration, Instant};
 use std::sync::atomic::{Ordering, AtomicUsize, ATOMIC_USIZE_INIT};
 use smallvec::SmallVec;
 
@@ -34,6 +35,7 @@ pub mod components;
 pub mod widgets;
 pub mod dag;
 pub mod entry;
+pub mod numeric;
 mod cache;
 #[allow(dead_code)]
 mod qtree;
@@ -47,6 +49,7 @@ pub use self::widgets::*;
 pub use self::components::*;
 pub use self::loadable::*;
 pub use self::identify_first_last::*;
+pub use self::numeric::*;
 use self::cache::Cache;
 use self::systems::*;
 
@@ -102,11 +105,23 @@ impl MousePosition {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone,Copy,PartialEq,Debug)]
 pub enum Layer {
     Back,
     Normal,
-    Modal
+    Modal,
+    Overlay,
+}
+
+impl Layer {
+    fn rank(&self) -> usize {
+        match self {
+            &Layer::Back => 0,
+            &Layer::Normal => 1,
+            &Layer::Modal => 2,
+            &Layer::Overlay => 3,
+        }
+    }
 }
 
 struct UiLayer {
@@ -128,9 +143,11 @@ pub struct Ui {
     sys_render_post: Vec<Box<SystemDispatch<Vec<Primitive>>>>,
     sys_event: Vec<Box<SystemDispatch<EventSystemContext>>>,
     events: EventVec,
+    overlay: Rc<RefCell<Vec<Primitive>>>,
     cache: Cache,
-    tabstop_last_id: Option<dag::Id>,
-    tabstop_focus_id: Option<dag::Id>,
+    // The widgets of the layers that are being built, in the order they were added, and whether
+    //  they are a tabstop
+    tabstops: Vec<Vec<(dag::Id, bool)>>,
     pub viewport: Rect,
     pub cursor: (f32, f32),
     active_layer: String,
@@ -140,6 +157,16 @@ pub struct Ui {
     mouse_mode: MouseMode,
     layout_solver: cassowary::Solver,
     layout_lookup: HashMap<cassowary::Variable, dag::Id>,
+    tooltip_delay: Duration,
+    tooltip_hover: Option<(dag::Id, Instant)>,
+    tooltip_candidate: Option<dag::Id>,
+    caret: Option<Rect>,
+    // The texts of the input method events of this update, and of the events for the next one
+    compositions: Vec<String>,
+    queued_compositions: Vec<String>,
+    // A rect that the enclosing `Scroll` keeps in view in this update, and one for the next update
+    reveal: Option<Rect>,
+    queued_reveal: Option<Rect>,
 
     viewport_left: cassowary::Variable,
     viewport_top: cassowary::Variable,
@@ -169,6 +196,7 @@ pub struct WidgetResult<'a, T: 'a + Widget> {
     pub result: T::Result,
     pub context: Context<'a>,
     pub internal_id: dag::Id,
+    pub hovered: bool,
 }
 
 static INSTANCE_COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
@@ -178,6 +206,8 @@ impl Ui {
 
         let (clip_push, clip_pop) = new_clip_system();
 
+        let overlay = Rc::new(RefCell::new(Vec::new()));
+
         let sys_render: Vec<Box<SystemDispatch<Vec<Primitive>>>> = vec![
             Box::new(BackgroundRenderSystem{}),
             Box::new(clip_push),
@@ -187,6 +217,7 @@ impl Ui {
             Box::new(TextRenderSystem{}),
             Box::new(clip_pop),
             Box::new(DrawingRenderSystem{}),
+            Box::new(OverlayRenderSystem::new(overlay.clone())),
         ];
 
         let sys_event: Vec<Box<SystemDispatch<EventSystemContext>>> = vec![
@@ -236,9 +267,9 @@ impl Ui {
             sys_render_post,
             sys_event,
             events: EventVec::new(),
+            overlay,
             cache: Cache::new(2048, INSTANCE_COUNTER.fetch_add(1, Ordering::SeqCst)),
-            tabstop_last_id: None,
-            tabstop_focus_id: None,
+            tabstops: Vec::new(),
             viewport: Rect::from_wh(0.0, 0.0),
             cursor: (0.0, 0.0),
             active_layer: String::from(""),
@@ -248,6 +279,14 @@ impl Ui {
             mouse_mode: MouseMode::Normal,
             layout_solver,
             layout_lookup: HashMap::new(),
+            tooltip_delay: Duration::from_millis(500),
+            tooltip_hover: None,
+            tooltip_candidate: None,
+            caret: None,
+            compositions: Vec::new(),
+            queued_compositions: Vec::new(),
+            reveal: None,
+            queued_reveal: None,
             viewport_left,
             viewport_top,
             viewport_right,
@@ -263,6 +302,11 @@ impl Ui {
         }
     }
 
+    // Set how long a widget must be hovered before it's tooltip is shown.
+    pub fn set_tooltip_delay(&mut self, delay: Duration) {
+        self.tooltip_delay = delay;
+    }
+
     pub fn get_patch<'a, L: Loadable<'a>>(&mut self, load: L) -> Patch {
         self.cache.get_patch(load)
     }
@@ -303,6 +347,9 @@ impl Ui {
                         for c in old_layout.constraints() {
                             layout_solver.remove_constraint(c).expect("Layout crash");
                         }
+                        for e in old_layout.edits() {
+                            layout_solver.remove_edit_variable(*e).ok();
+                        }
                     });
                 }
 
@@ -346,6 +393,9 @@ impl Ui {
         self.events = events;
 
         self.capture = Capture::None;
+        self.caret = None;
+        self.compositions = replace(&mut self.queued_compositions, Vec::new());
+        self.reveal = self.queued_reveal.take();
 
         if self.events.len() > 0 {
             self.mouse_style = MouseStyle::Arrow;
@@ -353,19 +403,39 @@ impl Ui {
             self.events.push(Event::Idle);
         }
 
-        let mut no_modal_found = true;
-        self.active_layer = self.layers.iter().rev().find(|x| {
-            if x.layer == Layer::Modal {
-                no_modal_found =  false;
-                true
-            } else {
-                self.cursor.0 > x.rect.left &&
-                self.cursor.0 < x.rect.right &&
-                self.cursor.1 > x.rect.top &&
-                self.cursor.1 < x.rect.bottom &&
-                no_modal_found
-            }
-        }).map(|x| x.id.clone()).unwrap_or("".to_string());
+        // restart the tooltip timer when another widget is hovered or when something is pressed
+        let pressed = self.events.iter().any(|event| match event {
+            &Event::Press(_, _) => true,
+            _ => false,
+        });
+        let candidate = self.tooltip_candidate.take();
+        if pressed || candidate != self.tooltip_hover.map(|(id, _)| id) {
+            self.tooltip_hover = candidate.map(|id| (id, Instant::now()));
+        }
+
+        // keep the active layer while a widget is capturing, so the capturing widget can keep
+        //  receiving input when the cursor leaves the layer (e.g. for popups).
+        let captured = match self.previous_capture {
+            Capture::CaptureFocus(_) | Capture::CaptureMouse(_) => true,
+            _ => false,
+        };
+
+        // overlay layers never receive input
+        if !captured {
+            let mut no_modal_found = true;
+            self.active_layer = self.layers.iter().rev().filter(|x| x.layer != Layer::Overlay).find(|x| {
+                if x.layer == Layer::Modal {
+                    no_modal_found =  false;
+                    true
+                } else {
+                    self.cursor.0 > x.rect.left &&
+                    self.cursor.0 < x.rect.right &&
+                    self.cursor.1 > x.rect.top &&
+                    self.cursor.1 < x.rect.bottom &&
+                    no_modal_found
+                }
+            }).map(|x| x.id.clone()).unwrap_or("".to_string());
+        }
     }
 
     pub fn layer<'a>(&'a mut self, style: &'a Style, id: &'a str, layer: Layer) -> Context<'a> {
@@ -414,6 +484,7 @@ impl Ui {
         };
 
         self.tree_stack.push(tree.unwrap());
+        self.tabstops.push(Vec::new());
 
         let window = if self.active_layer == id {
             Some(self.viewport)
@@ -434,19 +505,7 @@ impl Ui {
 
     pub fn render(&mut self) -> (DrawList, MouseStyle, MouseMode) {
         // Sort layers by type, indepently of last clicked sorting
-        self.layers.sort_by(|a,b| {
-            let a = match &a.layer {
-                Layer::Back => 0,
-                Layer::Normal => 1,
-                Layer::Modal => 2,
-            };
-            let b = match &b.layer {
-                Layer::Back => 0,
-                Layer::Normal => 1,
-                Layer::Modal => 2,
-            };
-            a.cmp(&b)
-        });
+        self.layers.sort_by(|a,b| a.layer.rank().cmp(&b.layer.rank()));
 
         // Dispatch render systems
         let mut layers = replace(&mut self.layers, vec![]);
@@ -461,6 +520,14 @@ impl Ui {
             } 
         }
 
+        // Overlays are drawn on top of all layers, without being clipped by their parents
+        let mut overlay = replace(self.overlay.borrow_mut().deref_mut(), vec![]);
+        if overlay.len() > 0 {
+            overlay.insert(0, Primitive::PushClip(self.viewport));
+            overlay.push(Primitive::PopClip);
+            drawlists.push(overlay);
+        }
+
         // Remove unused layers
         layers.retain(|ly| ly.used >= self.iteration);
 
@@ -476,6 +543,103 @@ impl Ui {
         (drawlist, self.mouse_style, self.mouse_mode)
     }
 
+    // Move a layer in front of all other layers of the same `Layer` type.
+    // Returns false if no layer with the given id exists.
+    pub fn bring_to_front(&mut self, id: &str) -> bool {
+        match self.layers.iter().position(|ly| ly.id == id) {
+            Some(i) => {
+                let ly = self.layers.remove(i);
+                self.layers.push(ly);
+                true
+            },
+            None => false,
+        }
+    }
+
+    // Move a layer behind all other layers of the same `Layer` type.
+    // Returns false if no layer with the given id exists.
+    pub fn send_to_back(&mut self, id: &str) -> bool {
+        match self.layers.iter().position(|ly| ly.id == id) {
+            Some(i) => {
+                let ly = self.layers.remove(i);
+                self.layers.insert(0, ly);
+                true
+            },
+            None => false,
+        }
+    }
+
+    // The ids of all layers in the order they will be drawn, back to front.
+    pub fn layer_order(&self) -> Vec<&str> {
+        let mut order: Vec<&UiLayer> = self.layers.iter().collect();
+        order.sort_by(|a,b| a.layer.rank().cmp(&b.layer.rank()));
+        order.into_iter().map(|ly| ly.id.as_str()).collect()
+    }
+
+    // Give keyboard focus to a widget. The path starts with the id of the layer, followed by the
+    //  ids of the widget and it's parents as they were passed to `Context::add`.
+    // The layer is brought to the front. Returns false if the widget could not be found.
+    pub fn set_focus(&mut self, path: &[&str]) -> bool {
+        match self.find_widget(path) {
+            Some(id) => {
+                self.focus = Some(id);
+                self.bring_to_front(path[0]);
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn clear_focus(&mut self) {
+        self.focus = None;
+    }
+
+    // The caret of the focused text field, if there is one. This can be used to place the 
+    //  candidate window of an input method.
+    pub fn caret_rect(&self) -> Option<Rect> {
+        self.caret
+    }
+
+    // Store the text of an input method event. The event has to be passed to the next `update()`.
+    pub fn composition_text(&mut self, text: &str) -> CompositionText {
+        self.queued_compositions.push(text.to_string());
+        CompositionText(self.queued_compositions.len() - 1)
+    }
+
+    // The text of an input method event of the current update
+    pub fn composed(&self, text: CompositionText) -> &str {
+        self.compositions.get(text.0).map_or("", |text| text.as_str())
+    }
+
+    // The events an input method sends while `text` is typed and committed. Useful as a
+    //  synthetic event source on platforms without input method support, and for testing.
+    pub fn composition(&mut self, text: &str) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut preedit = String::new();
+        for c in text.chars() {
+            preedit.push(c);
+            let count = preedit.chars().count();
+            events.push(Event::Preedit(self.composition_text(&preedit), Some((count, count))));
+        }
+        events.push(Event::Preedit(self.composition_text(""), None));
+        events.push(Event::Commit(self.composition_text(text)));
+        events
+    }
+
+    fn find_widget(&self, path: &[&str]) -> Option<dag::Id> {
+        let (layer, path) = path.split_first()?;
+        let mut tree = self.layers.iter().find(|ly| ly.id == *layer)?.tree.as_ref();
+        let mut found = None;
+
+        for name in path {
+            let item = tree?.ids.get(*name)?;
+            found = Some(item.id);
+            tree = item.subs.as_ref();
+        }
+
+        found
+    }
+
     pub fn children(&self) -> impl Iterator<Item=&dag::Id> {
         let top = self.tree_stack.len() - 1;
         self.tree_stack[top].ord.iter()
@@ -523,6 +687,52 @@ impl Ui {
         });     
     }
 
+    // The current rects of all widgets that have a component of type `T`
+    fn rects_with<T: 'static + Clone>(&self) -> Vec<(dag::Id, Rect)> {
+        let components = self.containers
+            .get(&TypeId::of::<T>())
+            .and_then(|x| x.downcast_ref::<Container<T>>());
+        let layouts = self.containers
+            .get(&TypeId::of::<Layout>())
+            .and_then(|x| x.downcast_ref::<Container<Layout>>());
+
+        match (components, layouts) {
+            (Some(components), Some(layouts)) => {
+                let layouts = layouts.borrow();
+                let rects = components.borrow().iter().enumerate().filter_map(|(id, &(ref component, gen))| {
+                    // the layout of a removed widget is gone, the other components are left behind
+                    component.as_ref()
+                        .and_then(|_| layouts.get(id))
+                        .and_then(|&(ref layout, layout_gen)| if layout_gen == gen { layout.as_ref() } else { None })
+                        .and_then(|layout| layout.current)
+                        .map(|rect| ((id, gen), rect))
+                }).collect();
+                rects
+            },
+            _ => vec![],
+        }
+    }
+
+    // Scroll `rect` into view in the scrolls that contain it, when they are updated next
+    fn reveal(&mut self, rect: Rect) {
+        self.queued_reveal = Some(rect);
+    }
+
+    // The part of the viewport where the widget that is being updated can be seen. The rects of
+    //  all its parents are intersected, so it is clipped by an enclosing `Scroll`. Unlike the
+    //  input rect it doesn't depend on the layer being active.
+    fn visible_rect(&self) -> Option<Rect> {
+        let parents = self.tree_stack.len().saturating_sub(1);
+        self.tree_stack[..parents].iter().fold(Some(self.viewport), |visible, tree| {
+            let var = |name: &str| tree.vars.get(name).map(|&variable| self.layout_solver.get_value(variable) as f32);
+            match (var("super.left"), var("super.top"), var("super.right"), var("super.bottom")) {
+                (Some(left), Some(top), Some(right), Some(bottom)) =>
+                    visible.and_then(|visible| visible.intersect(&Rect { left, top, right, bottom })),
+                _ => visible,
+            }
+        })
+    }
+
     pub fn component<T: 'static + Clone>(&self, (id, gen): dag::Id) -> Option<FetchComponent<T>> {
         self.containers
             .get(&TypeId::of::<T>())
@@ -553,6 +763,8 @@ impl<'a> Context<'a> {
             tree.ord.push(internal_id);
             let item = tree.item(id, free);
 
+            self.parent.tabstops.last_mut().map(|stops| stops.push((internal_id, w.tabstop())));
+
             (internal_id, 0 == replace(&mut item.used, iteration), item.subs.take().unwrap_or(dag::Tree::new()))
         };
 
@@ -595,6 +807,16 @@ impl<'a> Context<'a> {
 
         let sub_window = w.update(internal_id, self.parent, self.style, self.window.clone());
 
+        let cursor = MousePosition {
+            x: self.parent.cursor.0,
+            y: self.parent.cursor.1,
+            visibility: self.window,
+        };
+        let hovered = self.parent.component(internal_id).map_or(false, |layout: FetchComponent<Layout>| {
+            let layout = layout.borrow();
+            layout.current().map_or(false, |current| cursor.inside(current))
+        });
+
         let widgets = &mut self.widgets;
 
         let context = Context {
@@ -615,6 +837,7 @@ impl<'a> Context<'a> {
             result,
             context,
             internal_id,
+            hovered,
         }
     }
 
@@ -667,6 +890,44 @@ impl<'a, T: 'static + Widget> WidgetResult<'a, T> {
         ]);
         self.result
     }
+
+    // Show a text tooltip when the widget is hovered for a while.
+    pub fn tooltip(self, text: &str) -> Self {
+        let text = text.to_string();
+        self.tooltip_with(|ui| {
+            ui.add("text", Label::simple_owned(text));
+            ui.rules(|var| vec![
+                var("text.left") |EQ(REQUIRED)| var("super.margin_left"),
+                var("text.right") |EQ(REQUIRED)| var("super.margin_right"),
+                var("text.top") |EQ(REQUIRED)| var("super.margin_top"),
+                var("text.bottom") |EQ(REQUIRED)| var("super.margin_bottom"),
+            ]);
+        })
+    }
+
+    // Show a tooltip with custom content when the widget is hovered for a while.
+    // The closure is only called when the tooltip is visible. The tooltip is sized by the
+    //  layout rules of it's content.
+    pub fn tooltip_with<F: FnOnce(&mut Context)>(mut self, f: F) -> Self {
+        if self.hovered {
+            let id = self.internal_id;
+            let style = self.context.style;
+            let ui = &mut *self.context.parent;
+
+            // the last hovered widget in a frame is the innermost one, so it wins.
+            ui.tooltip_candidate = Some(id);
+
+            let visible = ui.tooltip_hover.map_or(false, |(hover, since)| {
+                hover == id && since.elapsed() >= ui.tooltip_delay
+            });
+
+            if visible {
+                let mut layer = ui.layer(style, "__tooltip", Layer::Overlay);
+                f(&mut layer.add("tooltip", Tooltip::new()).context);
+            }
+        }
+        self
+    }
 }
 
 // When the context is dropped, events and rendering will be evaluated and the results will be 
@@ -725,12 +986,12 @@ impl<'a> Drop for Context<'a> {
                         },
                         _ => if focused {
                             match event {
-                                Event::Press(Key::Tab, Modifiers{ shift: false, .. }) => {
+                                // ctrl+tab is left for switching between tabs
+                                Event::Press(Key::Tab, Modifiers{ shift: false, ctrl: false, .. }) => {
                                     self.parent.capture = Capture::FocusNext;
                                 },
-                                Event::Press(Key::Tab, Modifiers{ shift: true, .. }) => {
+                                Event::Press(Key::Tab, Modifiers{ shift: true, ctrl: false, .. }) => {
                                     self.parent.capture = Capture::FocusPrev;
-                                    self.parent.tabstop_focus_id = self.parent.tabstop_last_id;
                                 },
                                 _ => (),
                             }
@@ -764,8 +1025,6 @@ impl<'a> Drop for Context<'a> {
                 self.parent.tree_stack[top].ids.get_mut(src).unwrap().subs = Some(tree);
             }
         } else {
-            assert!(self.parent.tree_stack.len() == 0);
-
             let mut layer_rect = None;
             for (id, _) in widgets {
                 self.parent.component(id).map(|layout: FetchComponent<Layout>| {
@@ -776,6 +1035,26 @@ impl<'a> Drop for Context<'a> {
                 });
             }
 
+            // tab moves the focus to the next tabstop of the layer. it wraps around at the end,
+            //  so the focus never leaves the layer
+            let stops = self.parent.tabstops.pop().unwrap_or(Vec::new());
+            let forward = match self.parent.capture {
+                Capture::FocusNext => Some(true),
+                Capture::FocusPrev => Some(false),
+                _ => None,
+            };
+            let current = self.parent.focus.and_then(|focus| stops.iter().position(|&(id, _)| id == focus));
+            if let (Some(forward), Some(current)) = (forward, current) {
+                let count = stops.len();
+                let next = (1..count + 1)
+                    .map(|i| if forward { (current + i) % count } else { (current + count - i) % count })
+                    .map(|i| stops[i])
+                    .find(|&(_, tabstop)| tabstop);
+                if let Some((next, _)) = next {
+                    self.parent.focus = Some(next);
+                }
+            }
+
             let id = self.id;
             let ly = self.parent.layers.iter_mut().rev().find(|ly| ly.id == id).unwrap();
             ly.tree = Some(tree);
@@ -849,6 +1128,8 @@ impl Ui {
         for primitive in draw_lists.into_iter().flat_map(|d| d) {
             match primitive {
                 Primitive::PushClip(scissor) => {
+                    // a clip can never draw outside of the clip it is nested in
+                    let scissor = scissor.intersect(&scissors[scissors.len()-1]).unwrap_or(Rect::zero());
                     scissors.push(scissor);
 
                     draw_enabled = validate_clip(scissor).map_or_else(
@@ -1051,7 +1332,11 @@ impl Ui {
         // Resolve mouse mode and style for current frame
         let (mouse_style, mouse_mode) = match self.capture {
             Capture::CaptureFocus(style) => (style, MouseMode::Normal),
-            Capture::CaptureMouse(style) => (style, MouseMode::Confined),
+            Capture::CaptureMouse(style) => match self.mouse_mode {
+                // a widget asked for the cursor to stay in place while it's dragged
+                MouseMode::Locked(x, y) => (style, MouseMode::Locked(x, y)),
+                _ => (style, MouseMode::Confined),
+            },
             _ => (self.mouse_style, MouseMode::Normal)
         };
 
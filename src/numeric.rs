@@ -0,0 +1,42 @@
+// Numeric types that can be edited by widgets like `Slider` and `NumberInput`.
+// All calculations are done in `f64`, conversion back to the numeric type rounds when needed.
+pub trait Numeric: Copy + PartialOrd {
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+    // Parse user input, returns `None` if the text is not a valid number.
+    fn parse(text: &str) -> Option<Self>;
+    // Format the value with the given number of decimals. Integers ignore the precision.
+    fn format(self, precision: usize) -> String;
+    // Integers can only change in whole steps
+    fn is_integer() -> bool;
+}
+
+macro_rules! impl_numeric_float {
+    ($($t:ty),*) => ($(
+        impl Numeric for $t {
+            fn to_f64(self) -> f64 { self as f64 }
+            fn from_f64(value: f64) -> Self { value as $t }
+            fn parse(text: &str) -> Option<Self> { text.trim().parse::<$t>().ok() }
+            fn format(self, precision: usize) -> String { format!("{:.*}", precision, self) }
+            fn is_integer() -> bool { false }
+        }
+    )*)
+}
+
+macro_rules! impl_numeric_int {
+    ($($t:ty),*) => ($(
+        impl Numeric for $t {
+            fn to_f64(self) -> f64 { self as f64 }
+            fn from_f64(value: f64) -> Self { value.round() as $t }
+            fn parse(text: &str) -> Option<Self> { 
+                let text = text.trim();
+                text.parse::<$t>().ok().or_else(|| text.parse::<f64>().ok().map(Self::from_f64))
+            }
+            fn format(self, _precision: usize) -> String { format!("{}", self) }
+            fn is_integer() -> bool { true }
+        }
+    )*)
+}
+
+impl_numeric_float!(f32, f64);
+impl_numeric_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
@@ -1,10 +1,14 @@
 use super::*;
 use std::f32;
 
+// Every mode starts a new line at '\n', so a label with line breaks has more than one line
 #[derive(Clone,Copy,Debug)]
 pub enum TextWrap {
+    // Lines only break at '\n'
     NoWrap,
+    // Lines also break at the character that doesn't fit the width
     Wrap,
+    // Lines also break before the word that doesn't fit the width, longer words are broken up
     WordWrap,
 }
 
@@ -32,6 +36,13 @@ impl<'a, 'b> Iterator for CharPositionIter<'a, 'b> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.base.next().and_then(|c| {
+            if c == '\n' {
+                // line breaks are laid out as an empty glyph without width
+                let g = self.font.glyph(' ').unwrap().scaled(self.scale);
+                self.last = None;
+                return Some((c, g, self.x, self.x));
+            }
+
             let g = self.font.glyph(c).unwrap();
             let g = g.scaled(self.scale);
             let w = g.h_metrics().advance_width 
@@ -48,68 +59,6 @@ impl<'a, 'b> Iterator for CharPositionIter<'a, 'b> {
     }
 }
 
-struct WordWrapper<'a, 'b: 'a> {
-    x: f32,
-    y: f32,
-    final_x: f32,
-    final_y: f32,
-    width: f32,
-    height: f32,
-    iter: CharPositionIter<'a, 'b>,
-    f: &'a mut FnMut(rusttype::ScaledGlyph<'static>, f32, f32, f32),
-}
-
-impl<'a, 'b: 'a> WordWrapper<'a, 'b> {
-    fn layout_word(
-        &mut self,
-        glyph: rusttype::ScaledGlyph<'static>, 
-        a: f32,
-        b: f32, 
-        c: f32,
-        mut word: bool,
-    ) {
-        if word {
-            self.x = self.final_x;
-            self.y = self.final_y;
-
-            if let Some((ch, glyph, b, c)) = self.iter.next() {
-                if ch.is_alphanumeric() {
-                    if c-self.x > self.width {
-                        self.x = a;
-                        self.y += self.height;
-                        word = false;
-                    }
-                    self.layout_word(glyph, a, b, c, word);
-                }
-            }
-
-            (self.f)(glyph, b - self.x, c - self.x, self.y);
-        }  else {
-            self.final_x = self.x;
-            self.final_y = self.y;
-
-            if c-self.final_x > self.width {
-                self.final_x = b;
-                self.final_y += self.height;
-            }
-            (self.f)(glyph, b - self.final_x, c - self.final_x, self.final_y);
-
-            while let Some((ch, glyph, b, c)) = self.iter.next() {
-                if c-self.final_x > self.width {
-                    self.final_x = b;
-                    self.final_y += self.height;
-                }
-
-                (self.f)(glyph, b - self.final_x, c - self.final_x, self.final_y);
-                
-                if !ch.is_alphanumeric() {
-                    break;
-                }
-            }
-        }             
-    }
-}
-
 impl Text {
     pub fn char_positions<'a,'b>(&'b self) -> CharPositionIter<'a, 'b> {
         let scale = rusttype::Scale{ x: self.size, y: self.size };
@@ -122,6 +71,17 @@ impl Text {
         }
     }
 
+    // Distance between the baselines of two lines
+    pub fn line_height(&self) -> f32 {
+        let line = self.font.inner.v_metrics(rusttype::Scale{ x: self.size, y: self.size });
+        line.ascent - line.descent + line.line_gap
+    }
+
+    // Distance between the top of a line and it's baseline
+    pub fn ascent(&self) -> f32 {
+        self.font.inner.v_metrics(rusttype::Scale{ x: self.size, y: self.size }).ascent
+    }
+
     pub fn layout<
         F: FnMut(rusttype::ScaledGlyph<'static>, f32, f32, f32)
     > (
@@ -129,46 +89,90 @@ impl Text {
         rect: Rect, 
         mut f: F
     ) {
-        let line = self.font.inner.v_metrics(rusttype::Scale{ x: self.size, y: self.size });
-        
+        self.layout_chars(rect, |_, g, a, b, y| f(g, a, b, y));
+    }
+
+    // Same as `layout`, but the character of every glyph is passed as well.
+    // Line breaks are passed as a glyph without width at the end of the line they break.
+    fn layout_chars<
+        F: FnMut(char, rusttype::ScaledGlyph<'static>, f32, f32, f32)
+    > (
+        &self, 
+        rect: Rect, 
+        mut f: F
+    ) {
         let width = rect.width();
-        let height = -line.descent + line.line_gap + line.ascent;
+        let height = self.line_height();
+
+        let mut x = 0.0;
+        let mut y = self.ascent();
 
         match self.wrap {
             TextWrap::NoWrap => {
-                for (_, g, a, b) in self.char_positions() {
-                    f(g, a, b, line.ascent);
+                for (c, g, a, b) in self.char_positions() {
+                    f(c, g, a - x, b - x, y);
+
+                    if c == '\n' {
+                        x = b;
+                        y += height;
+                    }
                 }
             },
 
             TextWrap::Wrap => {
-                let mut x = 0.0;
-                let mut y = line.ascent;
-
-                for (_, g, a, b) in self.char_positions() {
-                    if b - x > width {
+                for (c, g, a, b) in self.char_positions() {
+                    if b - x > width && a > x {
                         x = a;
                         y += height;
                     }
 
-                    f(g, a - x, b - x, y);
+                    f(c, g, a - x, b - x, y);
+
+                    if c == '\n' {
+                        x = b;
+                        y += height;
+                    }
                 }
             },
 
             TextWrap::WordWrap => {
-                let mut wrapper = WordWrapper {
-                    x: 0.0, 
-                    y: line.ascent,
-                    final_x: 0.0,
-                    final_y: line.ascent,
-                    width: width,
-                    height: height,
-                    iter: self.char_positions(),
-                    f: &mut f,
-                };
-
-                while let Some((ch, glyph, a, b)) = wrapper.iter.next() {
-                    wrapper.layout_word(glyph, a, a, b, ch.is_alphanumeric());
+                let glyphs: Vec<_> = self.char_positions().collect();
+
+                let mut i = 0;
+                while i < glyphs.len() {
+                    let (c, _, a, _) = glyphs[i];
+
+                    // a word is a run of alphanumeric characters, any other character stands alone
+                    let end = if c.is_alphanumeric() {
+                        i + glyphs[i..].iter().take_while(|&&(c, _, _, _)| c.is_alphanumeric()).count()
+                    } else {
+                        i + 1
+                    };
+
+                    // whitespace is allowed to hang over the edge, other words move to the next line
+                    //  when they don't fit.
+                    let hang = c.is_whitespace();
+                    if !hang && glyphs[end - 1].3 - x > width && a > x {
+                        x = a;
+                        y += height;
+                    }
+
+                    for &(c, ref g, a, b) in glyphs[i..end].iter() {
+                        // words that are longer than a line are broken up
+                        if !hang && b - x > width && a > x {
+                            x = a;
+                            y += height;
+                        }
+
+                        f(c, g.clone(), a - x, b - x, y);
+
+                        if c == '\n' {
+                            x = b;
+                            y += height;
+                        }
+                    }
+
+                    i = end;
                 }
             },
         }
@@ -176,60 +180,93 @@ impl Text {
 
     pub fn measure(&self, rect: Option<Rect>) -> Rect {
         let line = self.font.inner.v_metrics(rusttype::Scale{ x: self.size, y: self.size });
+        let height = self.line_height();
+
+        let mut w: f32 = 0.0;
+        let mut h = line.ascent;
 
-        rect.map_or_else(
-            || {
-                let mut w = 0.0;
-                self.layout(Rect::from_wh(f32::INFINITY, 0.0), |_,_,new_w,_| w = new_w);
+        match rect {
+            None => {
+                self.layout_chars(Rect::from_wh(f32::INFINITY, 0.0), |c,_,_,new_w,new_h| {
+                    w = w.max(new_w);
+                    h = if c == '\n' { new_h + height } else { new_h };
+                });
 
-                Rect::from_wh(w.ceil(), (line.ascent - line.descent).ceil())
+                Rect::from_wh(w.ceil(), (h - line.descent).ceil())
             },
-            |r| {
-                let mut w = 0.0;
-                let mut h = line.ascent;
+            Some(r) => {
                 match self.wrap {
                     TextWrap::NoWrap => {
-                        self.layout(r, |_,_,new_w,_| w = new_w)
+                        self.layout_chars(r, |c,_,_,new_w,new_h| {
+                            w = w.max(new_w);
+                            h = if c == '\n' { new_h + height } else { new_h };
+                        });
                     },
                     TextWrap::Wrap | TextWrap::WordWrap => {
-                        w = rect.map_or(0.0, |r| r.width());
-                        self.layout(r, |_,_,_,new_h| h = new_h);
+                        w = r.width();
+                        self.layout_chars(r, |c,_,_,_,new_h| {
+                            h = if c == '\n' { new_h + height } else { new_h };
+                        });
                     },
                 }
 
                 Rect::from_xywh(r.left, r.top, w.ceil(), (h - line.descent).ceil())
-            })
+            },
+        }
     }
 
+    // Returns the caret positions of the character indices `from` and `to`.
+    // The y coordinate of a caret position is the baseline of it's line.
     pub fn measure_range(&self, from: usize, to: usize, rect: Rect) -> ((f32,f32), (f32,f32)) {
-        let mut from_result = (0.0, 0.0);
-        let mut to_result = (0.0, 0.0);
+        let mut from_result = (0.0, self.ascent());
+        let mut to_result = (0.0, self.ascent());
+
+        let height = self.line_height();
 
         let mut index = 0;
-        self.layout(rect, |_, begin, end, y| {
+        self.layout_chars(rect, |c, _, begin, end, y| {
+            // the caret after a line break is at the start of the next line
+            let end = if c == '\n' { (0.0, y + height) } else { (end, y) };
+
             if index == from { from_result = (begin, y) }
             if index == to { to_result = (begin, y) }
-            if index+1 == from { from_result = (end, y) }
-            if index+1 == to { to_result = (end, y) }
+            if index+1 == from { from_result = end }
+            if index+1 == to { to_result = end }
             index += 1;
         });
 
         (from_result, to_result)
     }
 
+    // Returns the caret position nearest to `cursor`. The line closest to the cursor is
+    //  chosen first, then the nearest position on that line.
     pub fn hitdetect(&self, cursor: (f32, f32), rect: Rect) -> usize {
-        let dist = |(x,y)| x*x+y*y;
-
-        let mut nearest = (dist(cursor), 0);
+        let line = self.font.inner.v_metrics(rusttype::Scale{ x: self.size, y: self.size });
+        let height = self.line_height();
+
+        let dist = |x: f32, y: f32| {
+            let dy = if cursor.1 < y - line.ascent {
+                y - line.ascent - cursor.1
+            } else if cursor.1 > y - line.descent + line.line_gap {
+                cursor.1 - (y - line.descent + line.line_gap)
+            } else {
+                0.0
+            };
+            (dy, (x - cursor.0).abs())
+        };
+
+        let mut nearest = (dist(0.0, line.ascent), 0);
         let mut index = 0;
 
-        self.layout(rect, |_,begin,end,y| {
-             if dist((begin-cursor.0, y-cursor.1)) < nearest.0 { 
-                nearest.0 = dist((begin-cursor.0, y-cursor.1));
+        self.layout_chars(rect, |c,_,begin,end,y| {
+            let end = if c == '\n' { (0.0, y + height) } else { (end, y) };
+
+            if dist(begin, y) < nearest.0 { 
+                nearest.0 = dist(begin, y);
                 nearest.1 = index;
             }
-            if dist((end-cursor.0, y-cursor.1)) < nearest.0 { 
-                nearest.0 = dist((end-cursor.0, y-cursor.1));
+            if dist(end.0, end.1) < nearest.0 { 
+                nearest.0 = dist(end.0, end.1);
                 nearest.1 = index+1;
             }
 
@@ -238,4 +275,4 @@ impl Text {
 
         nearest.1
     }
-}
\ No newline at end of file
+}
@@ -6,6 +6,7 @@ pub mod text_render;
 pub mod drawing_render;
 pub mod clickable_event;
 pub mod content_clip;
+pub mod overlay_render;
 
 pub use self::background_render::BackgroundRenderSystem;
 pub use self::text_render::TextRenderSystem;
@@ -14,6 +15,7 @@ pub use self::clickable_event::ClickableEventSystem;
 pub use self::content_clip::ContentPushClipSystem;
 pub use self::content_clip::ContentPopClipSystem;
 pub use self::content_clip::new_clip_system;
+pub use self::overlay_render::OverlayRenderSystem;
 
 pub trait System<C> {
     type Components: SystemComponents;
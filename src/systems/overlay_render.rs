@@ -0,0 +1,20 @@
+use super::*;
+
+pub struct OverlayRenderSystem {
+    target: Rc<RefCell<Vec<Primitive>>>,
+}
+
+impl OverlayRenderSystem {
+    pub fn new(target: Rc<RefCell<Vec<Primitive>>>) -> Self {
+        Self { target }
+    }
+}
+
+impl System<Vec<Primitive>> for OverlayRenderSystem {
+    type Components = (
+        FetchComponent<Overlay>
+    );
+    fn run(&self, _drawlist: &mut Vec<Primitive>, overlay: Self::Components) {
+        self.target.borrow_mut().extend(overlay.borrow().primitives.iter().cloned());
+    }
+}
@@ -22,6 +22,10 @@ impl Button {
 }
 
 impl WidgetBase for Button {
+    fn tabstop(&self) -> bool {
+        true
+    }
+
     fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
         let background = WidgetBackground{
             normal: Background::Patch(style.button_normal.clone(), 1.0),
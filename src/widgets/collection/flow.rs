@@ -0,0 +1,132 @@
+use super::*;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+// Spare width that is needed before two lines are joined again. Without it a collection whose
+//  width depends on its lines could switch between two splits every frame.
+const FLOW_HYSTERESIS: f32 = 16.0;
+
+// Horizontal alignment of the items within every line of a `FlowLayout`
+#[derive(Clone,Copy,PartialEq)]
+pub enum FlowAlign {
+    Leading,
+    Center,
+    Trailing,
+}
+
+// Places the items left to right and continues on a new line when the width of the
+//  collection is exceeded. The lines are decided with the sizes of the previous frame,
+//  so the items are laid out again when the collection or an item is resized. Lines are
+//  only joined again when the items fit with `FLOW_HYSTERESIS` to spare.
+pub struct FlowLayout {
+    align: FlowAlign,
+    spacing: (f32, f32),
+    // The lines of the current layout pass, they are decided once in `revision()`
+    lines: RefCell<Option<Vec<usize>>>,
+}
+
+impl FlowLayout {
+    pub fn new() -> Self {
+        Self {
+            align: FlowAlign::Leading,
+            spacing: (0.0, 0.0),
+            lines: RefCell::new(None),
+        }
+    }
+
+    // Horizontal space between the items and vertical space between the lines
+    pub fn with_spacing(mut self, horizontal: f32, vertical: f32) -> Self {
+        self.spacing = (horizontal, vertical);
+        self
+    }
+
+    pub fn with_align(mut self, align: FlowAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    // The index of the first item of every line
+    fn lines(&self, parent: &Layout, items: &[&Layout]) -> Vec<usize> {
+        let available = parent.current.map_or(0.0, |current| current.width());
+        let mut lines = vec![];
+        let mut x = 0.0;
+
+        for (i, item) in items.iter().enumerate() {
+            let width = item.current.map_or(0.0, |current| current.width());
+
+            // an item that started a line in the last frame only moves up with some spare width
+            let wrapped = i > 0 && match (items[i - 1].current, item.current) {
+                (Some(previous), Some(current)) => current.top > previous.top,
+                _ => false,
+            };
+            let needed = if wrapped { width + FLOW_HYSTERESIS } else { width };
+
+            if lines.is_empty() || x + self.spacing.0 + needed > available {
+                lines.push(i);
+                x = width;
+            } else {
+                x += self.spacing.0 + width;
+            }
+        }
+
+        lines
+    }
+}
+
+impl Layouter for FlowLayout {
+    fn item_among(&self, index: usize, parent: &Layout, items: &[&Layout]) -> Vec<cassowary::Constraint> {
+        let mut cached = self.lines.borrow_mut();
+        let lines = cached.get_or_insert_with(|| self.lines(parent, items));
+        let line = lines.iter().rposition(|&start| start <= index).unwrap_or(0);
+        let start = lines[line];
+        let end = lines.get(line + 1).cloned().unwrap_or(items.len()) - 1;
+        let (spacing_x, spacing_y) = (self.spacing.0 as f64, self.spacing.1 as f64);
+        let item = items[index];
+
+        let mut constraints = vec![
+            parent.bottom |GE(REQUIRED)| item.bottom,
+        ];
+
+        if index == 0 {
+            // keep the parent as small as the items allow
+            constraints.push(parent.height |EQ(WEAK)| 0.0);
+        }
+
+        if index > start {
+            // continue the line of the previous item
+            constraints.push(item.left |EQ(REQUIRED)| items[index - 1].right + spacing_x);
+            constraints.push(item.top |EQ(REQUIRED)| items[start].top);
+            return constraints;
+        }
+
+        // the first item of a line aligns the whole line
+        let last = items[end];
+        constraints.push(item.left |GE(REQUIRED)| parent.left);
+        constraints.push(match self.align {
+            FlowAlign::Leading => item.left |EQ(STRONG)| parent.left,
+            FlowAlign::Center => item.left - parent.left |EQ(STRONG)| parent.right - last.right,
+            FlowAlign::Trailing => last.right |EQ(STRONG)| parent.right,
+        });
+
+        if line == 0 {
+            constraints.push(item.top |EQ(REQUIRED)| parent.top);
+        } else {
+            // below every item of the line above
+            for other in items[lines[line - 1]..start].iter() {
+                constraints.push(item.top |GE(REQUIRED)| other.bottom + spacing_y);
+            }
+            constraints.push(item.top |EQ(WEAK)| parent.top);
+        }
+
+        constraints
+    }
+
+    fn revision(&self, parent: &Layout, items: &[&Layout]) -> usize {
+        let lines = self.lines(parent, items);
+        let mut hasher = DefaultHasher::new();
+        lines.hash(&mut hasher);
+        *self.lines.borrow_mut() = Some(lines);
+        hasher.finish() as usize
+    }
+}
@@ -0,0 +1,192 @@
+use super::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+pub enum GridColumns {
+    // A fixed number of columns that share the width of the collection
+    Count(usize),
+    // As many columns of at least this width as fit in the collection
+    Fit(f32),
+}
+
+pub enum GridRows {
+    // Every row has the same height
+    Uniform(f32),
+    // Every row is as high as it's highest item
+    Content,
+}
+
+#[derive(Clone,Copy)]
+struct Cell {
+    column: usize,
+    row: usize,
+    columns: usize,
+    rows: usize,
+}
+
+// The cells of the items that were placed so far, items are placed in order
+struct Placement {
+    count: usize,
+    occupied: Vec<Vec<bool>>,
+    position: usize,
+    cells: Vec<Cell>,
+}
+
+impl Placement {
+    fn new(count: usize) -> Self {
+        Self {
+            count,
+            occupied: vec![],
+            position: 0,
+            cells: vec![],
+        }
+    }
+
+    // Place the items up to `last` in the first free cells, row by row
+    fn place(&mut self, spans: &HashMap<usize, (usize, usize)>, last: usize) {
+        let count = self.count;
+
+        for i in self.cells.len()..last + 1 {
+            let (columns, rows) = spans.get(&i).cloned().unwrap_or((1, 1));
+            let columns = columns.min(count);
+
+            // find the first position where the span fits
+            loop {
+                let (row, column) = (self.position / count, self.position % count);
+                let occupied = &self.occupied;
+                let fits = column + columns <= count && (row..row + rows).all(|r| {
+                    (column..column + columns).all(|c| !occupied.get(r).map_or(false, |cells| cells[c]))
+                });
+                if fits {
+                    break;
+                }
+                self.position += 1;
+            }
+
+            let (row, column) = (self.position / count, self.position % count);
+            for r in row..row + rows {
+                while self.occupied.len() <= r {
+                    self.occupied.push(vec![false; count]);
+                }
+                for c in column..column + columns {
+                    self.occupied[r][c] = true;
+                }
+            }
+
+            self.cells.push(Cell { column, row, columns, rows });
+            self.position += columns;
+        }
+    }
+}
+
+pub struct GridLayout {
+    columns: GridColumns,
+    rows: GridRows,
+    spacing: (f32, f32),
+    spans: HashMap<usize, (usize, usize)>,
+    placement: RefCell<Placement>,
+}
+
+impl GridLayout {
+    pub fn new(columns: GridColumns, rows: GridRows) -> Self {
+        Self {
+            columns,
+            rows,
+            spacing: (0.0, 0.0),
+            spans: HashMap::new(),
+            placement: RefCell::new(Placement::new(0)),
+        }
+    }
+
+    // Horizontal and vertical space between the cells
+    pub fn with_spacing(mut self, horizontal: f32, vertical: f32) -> Self {
+        self.spacing = (horizontal, vertical);
+        self
+    }
+
+    // Let the item at `index` span multiple columns and rows
+    pub fn with_span(mut self, index: usize, columns: usize, rows: usize) -> Self {
+        self.spans.insert(index, (columns.max(1), rows.max(1)));
+        self
+    }
+
+    fn column_count(&self, parent: &Layout) -> usize {
+        match self.columns {
+            GridColumns::Count(count) => count.max(1),
+            GridColumns::Fit(width) => {
+                let available = parent.current.map_or(0.0, |current| current.width());
+                (((available + self.spacing.0) / (width + self.spacing.0)).floor() as usize).max(1)
+            },
+        }
+    }
+}
+
+impl Layouter for GridLayout {
+    fn item_among(&self, index: usize, parent: &Layout, items: &[&Layout]) -> Vec<cassowary::Constraint> {
+        let item = items[index];
+        let count = self.column_count(parent);
+
+        // the items are laid out in order, so the placement of the previous items is reused
+        let mut placement = self.placement.borrow_mut();
+        if placement.count != count || placement.cells.len() > index + 1 {
+            *placement = Placement::new(count);
+        }
+        placement.place(&self.spans, index);
+        let cells = &placement.cells;
+        let cell = cells[index];
+        let (spacing_x, spacing_y) = (self.spacing.0 as f64, self.spacing.1 as f64);
+
+        // the columns share the width of the parent
+        let n = count as f64;
+        let column_width = parent.width * (1.0 / n) - spacing_x * (n - 1.0) / n;
+        let left = parent.left + column_width.clone() * cell.column as f64 + spacing_x * cell.column as f64;
+        let width = column_width * cell.columns as f64 + spacing_x * (cell.columns as f64 - 1.0);
+
+        let mut constraints = vec![
+            item.left |EQ(REQUIRED)| left,
+            item.width |EQ(REQUIRED)| width,
+            parent.bottom |GE(REQUIRED)| item.bottom,
+        ];
+
+        if index == 0 {
+            // keep the parent as small as the items allow
+            constraints.push(parent.height |EQ(WEAK)| 0.0);
+        }
+
+        match self.rows {
+            GridRows::Uniform(height) => {
+                let height = height as f64;
+                let top = cell.row as f64 * (height + spacing_y);
+                constraints.push(item.top |EQ(REQUIRED)| parent.top + top);
+                constraints.push(item.height |EQ(REQUIRED)| height * cell.rows as f64 + spacing_y * (cell.rows as f64 - 1.0));
+            },
+            GridRows::Content => {
+                if cell.row == 0 {
+                    constraints.push(item.top |EQ(REQUIRED)| parent.top);
+                } else {
+                    // below every item that ends in the row above, aligned with the row.
+                    // rows never decrease with the index, so earlier items can't reach this row
+                    let tallest = self.spans.values().map(|&(_, rows)| rows).max().unwrap_or(1);
+                    for (other, other_cell) in items[..index].iter().zip(cells.iter()).rev() {
+                        if other_cell.row + tallest < cell.row {
+                            break;
+                        }
+                        if other_cell.row + other_cell.rows == cell.row {
+                            constraints.push(item.top |GE(REQUIRED)| other.bottom + spacing_y);
+                        }
+                        if other_cell.row == cell.row {
+                            constraints.push(item.top |EQ(REQUIRED)| other.top);
+                        }
+                    }
+                    constraints.push(item.top |EQ(WEAK)| parent.top);
+                }
+            },
+        }
+
+        constraints
+    }
+
+    fn revision(&self, parent: &Layout, _items: &[&Layout]) -> usize {
+        self.column_count(parent)
+    }
+}
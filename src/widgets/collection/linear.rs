@@ -1,4 +1,6 @@
 use super::*;
+use cassowary::strength::MEDIUM;
+use std::collections::HashMap;
 
 pub enum ContentAlign {
     Leading,
@@ -6,198 +8,239 @@ pub enum ContentAlign {
     Trailing,
 }
 
-pub struct TopToBottomLayout{
-    align: ContentAlign,
+// How the free space is distributed along the direction of the layout
+#[derive(Clone,Copy,PartialEq)]
+pub enum Justify {
+    // The items are next to each other, the last item reaches the end
+    Packed,
+    // Equal space between the items, none before the first and after the last item
+    SpaceBetween,
+    // Equal space around every item, so the ends get half the space between two items
+    SpaceAround,
+    // Equal space between the items and at both ends
+    SpaceEvenly,
 }
 
-pub struct LeftToRightLayout{
-    align: ContentAlign,
+#[derive(Clone,Copy)]
+enum Direction {
+    TopToBottom,
+    LeftToRight,
+    BottomToTop,
+    RightToLeft,
 }
 
-pub struct BottomToTopLayout{
-    align: ContentAlign,
-}
+impl Direction {
+    fn start(&self, layout: &Layout) -> cassowary::Variable {
+        match *self {
+            Direction::TopToBottom => layout.top,
+            Direction::LeftToRight => layout.left,
+            Direction::BottomToTop => layout.bottom,
+            Direction::RightToLeft => layout.right,
+        }
+    }
 
-pub struct RightToLeftLayout{
-    align: ContentAlign,
-}
+    fn end(&self, layout: &Layout) -> cassowary::Variable {
+        match *self {
+            Direction::TopToBottom => layout.bottom,
+            Direction::LeftToRight => layout.right,
+            Direction::BottomToTop => layout.top,
+            Direction::RightToLeft => layout.left,
+        }
+    }
 
-impl TopToBottomLayout {
-    pub fn new(align: ContentAlign) -> Self { Self { align } }
-}
+    fn size(&self, layout: &Layout) -> cassowary::Variable {
+        match *self {
+            Direction::TopToBottom | Direction::BottomToTop => layout.height,
+            Direction::LeftToRight | Direction::RightToLeft => layout.width,
+        }
+    }
 
-impl LeftToRightLayout {
-    pub fn new(align: ContentAlign) -> Self { Self { align } }
-}
+    // The edges across the direction, always left/top first
+    fn cross(&self, layout: &Layout) -> (cassowary::Variable, cassowary::Variable) {
+        match *self {
+            Direction::TopToBottom | Direction::BottomToTop => (layout.left, layout.right),
+            Direction::LeftToRight | Direction::RightToLeft => (layout.top, layout.bottom),
+        }
+    }
 
-impl BottomToTopLayout {
-    pub fn new(align: ContentAlign) -> Self { Self { align } }
+    // The padding at the start, the end and across the direction
+    fn padding(&self, padding: Rect) -> (f64, f64, f64, f64) {
+        let (start, end, cross_start, cross_end) = match *self {
+            Direction::TopToBottom => (padding.top, padding.bottom, padding.left, padding.right),
+            Direction::LeftToRight => (padding.left, padding.right, padding.top, padding.bottom),
+            Direction::BottomToTop => (padding.bottom, padding.top, padding.left, padding.right),
+            Direction::RightToLeft => (padding.right, padding.left, padding.top, padding.bottom),
+        };
+        (start as f64, end as f64, cross_start as f64, cross_end as f64)
+    }
+
+    // 1 when the direction runs along the coordinates, -1 when it runs against them
+    fn sign(&self) -> f64 {
+        match *self {
+            Direction::TopToBottom | Direction::LeftToRight => 1.0,
+            Direction::BottomToTop | Direction::RightToLeft => -1.0,
+        }
+    }
 }
 
-impl RightToLeftLayout {
-    pub fn new(align: ContentAlign) -> Self { Self { align } }
+// The shared implementation of the linear layouts
+struct Linear {
+    direction: Direction,
+    align: ContentAlign,
+    justify: Justify,
+    spacing: f32,
+    padding: Rect,
+    flex: HashMap<usize, (Constraint, f32)>,
 }
 
-impl Layouter for TopToBottomLayout {
-    fn header(&self, _: usize, item: &Layout, parent: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.top |EQ(REQUIRED)| parent.top,
-            if self.align.anchor_leading() {
-                item.left | EQ(REQUIRED) | parent.left
-            } else {
-                item.left | GE(REQUIRED) | parent.left
-            },
-            if self.align.anchor_trailing() {
-                item.right | EQ(REQUIRED) | parent.right
-            } else {
-                item.right | LE(REQUIRED) | parent.right
-            },
-        ]
+impl Linear {
+    fn new(direction: Direction, align: ContentAlign) -> Self {
+        Self {
+            direction,
+            align,
+            justify: Justify::Packed,
+            spacing: 0.0,
+            padding: Rect::zero(),
+            flex: HashMap::new(),
+        }
     }
 
-    fn item(&self, _: usize, item: &Layout, parent: &Layout, previous: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.top |EQ(REQUIRED)| previous.bottom,
-            if self.align.anchor_leading() {
-                item.left | EQ(REQUIRED) | parent.left
+    fn flex_weight(&self, index: usize) -> Option<f64> {
+        self.flex.get(&index).and_then(|&(ref flex, weight)| {
+            if *flex != Constraint::Fixed && weight > 0.0 {
+                Some(weight as f64)
             } else {
-                item.left | GE(REQUIRED) | parent.left
-            },
-            if self.align.anchor_trailing() {
-                item.right | EQ(REQUIRED) | parent.right
-            } else {
-                item.right | LE(REQUIRED) | parent.right
-            },
-        ]
+                None
+            }
+        })
     }
 
-    fn footer(&self, _: usize, item: &Layout, parent: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.bottom |EQ(REQUIRED)| parent.bottom
-        ]
-    }
-}
+    fn item_among(&self, index: usize, parent: &Layout, items: &[&Layout]) -> Vec<cassowary::Constraint> {
+        let d = self.direction;
+        let s = d.sign();
+        let (pad_start, pad_end, pad_cross_start, pad_cross_end) = d.padding(self.padding);
+        let spacing = self.spacing as f64;
 
-impl Layouter for LeftToRightLayout {
-    fn header(&self, _: usize, item: &Layout, parent: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.left |EQ(REQUIRED)| parent.left,
-            if self.align.anchor_leading() {
-                item.top | EQ(REQUIRED) | parent.top
-            } else {
-                item.top | GE(REQUIRED) | parent.top
-            },
-            if self.align.anchor_trailing() {
-                item.bottom | EQ(REQUIRED) | parent.bottom
-            } else {
-                item.bottom | LE(REQUIRED) | parent.bottom
-            },
-        ]
-    }
+        let item = items[index];
+        let first = items[0];
+        let (item_cross_start, item_cross_end) = d.cross(item);
+        let (parent_cross_start, parent_cross_end) = d.cross(parent);
 
-    fn item(&self, _: usize, item: &Layout, parent: &Layout, previous: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.left |EQ(REQUIRED)| previous.right,
+        let mut constraints = vec![
             if self.align.anchor_leading() {
-                item.top | EQ(REQUIRED) | parent.top
+                item_cross_start |EQ(REQUIRED)| parent_cross_start + pad_cross_start
             } else {
-                item.top | GE(REQUIRED) | parent.top
+                item_cross_start |GE(REQUIRED)| parent_cross_start + pad_cross_start
             },
             if self.align.anchor_trailing() {
-                item.bottom | EQ(REQUIRED) | parent.bottom
+                item_cross_end |EQ(REQUIRED)| parent_cross_end - pad_cross_end
             } else {
-                item.bottom | LE(REQUIRED) | parent.bottom
+                item_cross_end |LE(REQUIRED)| parent_cross_end - pad_cross_end
             },
-        ]
-    }
+        ];
 
-    fn footer(&self, _: usize, item: &Layout, parent: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.right |EQ(REQUIRED)| parent.right
-        ]
-    }
-}
+        // free space before the first item
+        let lead = (d.start(first) - d.start(parent)) * s - pad_start;
 
-impl Layouter for BottomToTopLayout {
-    fn header(&self, _: usize, item: &Layout, parent: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.bottom |EQ(REQUIRED)| parent.bottom,
-            if self.align.anchor_leading() {
-                item.left | EQ(REQUIRED) | parent.left
-            } else {
-                item.left | GE(REQUIRED) | parent.left
-            },
-            if self.align.anchor_trailing() {
-                item.right | EQ(REQUIRED) | parent.right
-            } else {
-                item.right | LE(REQUIRED) | parent.right
-            },
-        ]
-    }
+        if index == 0 {
+            constraints.push(match self.justify {
+                Justify::Packed | Justify::SpaceBetween => lead.clone() |EQ(REQUIRED)| 0.0,
+                Justify::SpaceAround | Justify::SpaceEvenly => lead.clone() |GE(REQUIRED)| 0.0,
+            });
+        } else {
+            // free space between this item and the previous one
+            let gap = (d.start(item) - d.end(items[index - 1])) * s - spacing;
 
-    fn item(&self, _: usize, item: &Layout, parent: &Layout, previous: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.bottom |EQ(REQUIRED)| previous.top,
-            if self.align.anchor_leading() {
-                item.left | EQ(REQUIRED) | parent.left
-            } else {
-                item.left | GE(REQUIRED) | parent.left
-            },
-            if self.align.anchor_trailing() {
-                item.right | EQ(REQUIRED) | parent.right
+            if self.justify == Justify::Packed {
+                constraints.push(gap |EQ(REQUIRED)| 0.0);
             } else {
-                item.right | LE(REQUIRED) | parent.right
-            },
-        ]
-    }
+                constraints.push(gap.clone() |GE(REQUIRED)| 0.0);
 
-    fn footer(&self, _: usize, item: &Layout, parent: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.top |EQ(REQUIRED)| parent.top
-        ]
+                // all gaps are as large as the first one
+                let first_gap = (d.start(items[1]) - d.end(first)) * s - spacing;
+                if index > 1 {
+                    constraints.push(gap |EQ(REQUIRED)| first_gap);
+                } else if self.justify == Justify::SpaceAround {
+                    constraints.push(lead.clone() * 2.0 |EQ(REQUIRED)| first_gap);
+                } else if self.justify == Justify::SpaceEvenly {
+                    constraints.push(lead.clone() |EQ(REQUIRED)| first_gap);
+                }
+            }
+        }
+
+        if index + 1 == items.len() {
+            // free space after the last item
+            let trail = (d.end(parent) - d.end(item)) * s - pad_end;
+            constraints.push(match self.justify {
+                Justify::Packed | Justify::SpaceBetween => trail |EQ(REQUIRED)| 0.0,
+                Justify::SpaceAround | Justify::SpaceEvenly => trail |EQ(REQUIRED)| lead,
+            });
+        }
+
+        if let Some(weight) = self.flex_weight(index) {
+            // grow into the free space
+            constraints.push(d.size(item) |GE(MEDIUM)| d.size(parent));
+
+            // and share it with the flexible items before this one
+            if let Some(other) = (0..index).find(|&i| self.flex_weight(i).is_some()) {
+                let other_weight = self.flex_weight(other).unwrap();
+                let strength = if self.flex[&index].0 == Constraint::Fill { REQUIRED } else { MEDIUM };
+                constraints.push(d.size(item) * (1.0 / weight) |EQ(strength)| d.size(items[other]) * (1.0 / other_weight));
+            }
+        }
+
+        constraints
     }
 }
 
-impl Layouter for RightToLeftLayout {
-    fn header(&self, _: usize, item: &Layout, parent: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.right |EQ(REQUIRED)| parent.right,
-            if self.align.anchor_leading() {
-                item.top | EQ(REQUIRED) | parent.top
-            } else {
-                item.top | GE(REQUIRED) | parent.top
-            },
-            if self.align.anchor_trailing() {
-                item.bottom | EQ(REQUIRED) | parent.bottom
-            } else {
-                item.bottom | LE(REQUIRED) | parent.bottom
-            },
-        ]
-    }
+macro_rules! linear_layout {
+    ($name:ident, $direction:expr) => {
+        pub struct $name {
+            linear: Linear,
+        }
 
-    fn item(&self, _: usize, item: &Layout, parent: &Layout, previous: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.right |EQ(REQUIRED)| previous.left,
-            if self.align.anchor_leading() {
-                item.top | EQ(REQUIRED) | parent.top
-            } else {
-                item.top | GE(REQUIRED) | parent.top
-            },
-            if self.align.anchor_trailing() {
-                item.bottom | EQ(REQUIRED) | parent.bottom
-            } else {
-                item.bottom | LE(REQUIRED) | parent.bottom
-            },
-        ]
-    }
+        impl $name {
+            pub fn new(align: ContentAlign) -> Self { Self { linear: Linear::new($direction, align) } }
+
+            // Space between two items
+            pub fn with_spacing(mut self, spacing: f32) -> Self {
+                self.linear.spacing = spacing;
+                self
+            }
+
+            // Space between the edges of the collection and the items
+            pub fn with_padding(mut self, padding: Rect) -> Self {
+                self.linear.padding = padding;
+                self
+            }
 
-    fn footer(&self, _: usize, item: &Layout, parent: &Layout) -> Vec<cassowary::Constraint> {
-        vec![
-            item.left |EQ(REQUIRED)| parent.left
-        ]
+            pub fn with_justify(mut self, justify: Justify) -> Self {
+                self.linear.justify = justify;
+                self
+            }
+
+            // Let the item at `index` take a part of the free space in proportion to `weight`.
+            // `Constraint::Grow` items don't get smaller than their content,
+            //  `Constraint::Fill` items are always sized by their weight.
+            pub fn with_flex(mut self, index: usize, flex: Constraint, weight: f32) -> Self {
+                self.linear.flex.insert(index, (flex, weight));
+                self
+            }
+        }
+
+        impl Layouter for $name {
+            fn item_among(&self, index: usize, parent: &Layout, items: &[&Layout]) -> Vec<cassowary::Constraint> {
+                self.linear.item_among(index, parent, items)
+            }
+        }
     }
 }
 
+linear_layout!(TopToBottomLayout, Direction::TopToBottom);
+linear_layout!(LeftToRightLayout, Direction::LeftToRight);
+linear_layout!(BottomToTopLayout, Direction::BottomToTop);
+linear_layout!(RightToLeftLayout, Direction::RightToLeft);
+
 impl ContentAlign {
     fn anchor_leading(&self) -> bool {
         match self {
@@ -3,7 +3,11 @@ use cassowary::WeightedRelation::*;
 use super::*;
 
 mod linear;
+mod grid;
+mod flow;
 pub use self::linear::*;
+pub use self::grid::*;
+pub use self::flow::*;
 
 pub trait Layouter {
     fn header(&self,
@@ -30,6 +34,22 @@ pub trait Layouter {
     ) -> Vec<cassowary::Constraint> {
         vec![]
     }
+
+    // Constraints for layouters that need more than the previous item. `items` holds all
+    //  the items of the collection in order, the item itself is at `index`.
+    fn item_among(&self,
+                  _index: usize,
+                  _parent: &Layout,
+                  _items: &[&Layout]
+    ) -> Vec<cassowary::Constraint> {
+        vec![]
+    }
+
+    // All items are laid out again when this value changes, for layouts that depend on the
+    //  current size of the parent or the items.
+    fn revision(&self, _parent: &Layout, _items: &[&Layout]) -> usize {
+        0
+    }
 }
 
 pub struct Collection<L: Layouter> {
@@ -50,42 +70,67 @@ struct CacheItem {
     cons: Vec<cassowary::Constraint>,
 }
 
+#[derive(Clone)]
+struct Cache {
+    items: Vec<CacheItem>,
+    revision: usize,
+}
+
 impl<L: Layouter> WidgetBase for Collection<L> {
     fn create(&mut self, id: dag::Id, world: &mut Ui, _style: &Style) {
         // layout component
         world.create_component(id, Layout::new());
 
         // list of id's for the stored items.
-        world.create_component(id, Vec::<CacheItem>::new());
+        world.create_component(id, Cache { items: vec![], revision: 0 });
     }
 
     fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, input: Option<Rect>) -> Option<Rect> {
         let mut previous = None;
-        let mut previous_layout: Option<FetchComponent<Layout>> = None;
 
-        let mut cache: FetchComponent<Vec<CacheItem>> = world.component(id).unwrap();
+        let mut cache: FetchComponent<Cache> = world.component(id).unwrap();
         let mut cache = cache.borrow_mut();
+        let cache = &mut *cache;
 
-        let mut count = 0;
+        let children: Vec<(bool, bool, dag::Id)> = world.children()
+            .identify_first_last()
+            .map(|(is_first, is_last, &id)| (is_first, is_last, id))
+            .collect();
+        let count = children.len();
 
         let parent: FetchComponent<Layout> = world.component(id).unwrap();
+        let parent = parent.borrow();
+
+        let layouts: Vec<FetchComponent<Layout>> = children.iter()
+            .map(|&(_, _, id)| world.component(id).unwrap())
+            .collect();
+        let borrowed: Vec<_> = layouts.iter().map(|x| x.borrow()).collect();
+        let items: Vec<&Layout> = borrowed.iter().map(|x| &**x).collect();
 
         let mut old_constraints = vec![];
         let mut new_constraints = vec![];
 
+        // everything is laid out again when the layouter asks for it
+        let revision = self.layout.revision(&*parent, &items);
+        let mut invalidated = revision != cache.revision;
+        cache.revision = revision;
+
         // perform layout resolve for invalidated items
-        for (i, (is_first, is_last, &id)) in world.children().identify_first_last().enumerate() {
+        for (i, &(is_first, is_last, id)) in children.iter().enumerate() {
 
-            let item: FetchComponent<Layout> = world.component(id).unwrap();
+            let item = items[i];
 
-            let is_cached = cache.get(i).map(|x| x.id == id &&
+            let is_cached = !invalidated && cache.items.get(i).map(|x| x.id == id &&
                 x.prev == previous &&
                 x.first_last == (is_first, is_last)
             ).unwrap_or(false);
 
             if !is_cached {
+                // items after this one may depend on it
+                invalidated = true;
+
                 // queue old constraints for removal
-                cache.get(i).map(|x| {
+                cache.items.get(i).map(|x| {
                     for c in x.cons.iter() {
                         old_constraints.push(c.clone());
                     }
@@ -95,24 +140,15 @@ impl<L: Layouter> WidgetBase for Collection<L> {
                 let constraints = {
                     let mut x = vec![];
                     if is_first {
-                        x.append(&mut self.layout.header(i,
-                            &*item.borrow(),
-                            &*parent.borrow()
-                        ));
+                        x.append(&mut self.layout.header(i, item, &*parent));
                     }
                     if is_last {
-                        x.append(&mut self.layout.footer(i,
-                            &*item.borrow(),
-                            &*parent.borrow()
-                        ))
+                        x.append(&mut self.layout.footer(i, item, &*parent))
                     }
-                    previous_layout.as_ref().map(|previous| {
-                        x.append(&mut self.layout.item(i,
-                            &*item.borrow(),
-                            &*parent.borrow(),
-                            &*previous.borrow()
-                        ));
-                    });
+                    if i > 0 {
+                        x.append(&mut self.layout.item(i, item, &*parent, items[i - 1]));
+                    }
+                    x.append(&mut self.layout.item_among(i, &*parent, &items));
                     x
                 };
 
@@ -122,15 +158,15 @@ impl<L: Layouter> WidgetBase for Collection<L> {
                 }
 
                 // update cache
-                if i >= cache.len() {
-                    cache.push(CacheItem {
+                if i >= cache.items.len() {
+                    cache.items.push(CacheItem {
                         id: id,
                         first_last: (is_first, is_last),
                         prev: previous,
                         cons: constraints
                     });
                 } else {
-                    cache[i] = CacheItem {
+                    cache.items[i] = CacheItem {
                         id: id,
                         first_last: (is_first, is_last),
                         prev: previous,
@@ -140,15 +176,13 @@ impl<L: Layouter> WidgetBase for Collection<L> {
             }
 
             previous = Some(id);
-            previous_layout = Some(item);
-            count += 1;
         }
 
         // cleanup old constraints
         for c in old_constraints {
             world.layout_solver.remove_constraint(&c).ok();
         }
-        for o in cache.split_off(count) {
+        for o in cache.items.split_off(count) {
             for c in o.cons.iter() {
                 world.layout_solver.remove_constraint(c).ok();
             }
@@ -157,7 +191,6 @@ impl<L: Layouter> WidgetBase for Collection<L> {
         // introduce new constraints
         world.layout_solver.add_constraints(new_constraints.iter()).ok();
 
-        let parent = parent.borrow();
         parent.current().and_then(|content| input.and_then(|ir| ir.intersect(&content)))
     }
 }
@@ -0,0 +1,399 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use super::*;
+use std::time::{Duration, Instant};
+
+// Maximum number of items that are visible in the popup before it starts scrolling
+const MAX_VISIBLE_ITEMS: usize = 8;
+// Typed characters that are this far apart start a new search
+const SEARCH_TIMEOUT: u64 = 1000;
+
+#[derive(Clone)]
+pub struct ComboBoxState {
+    open: bool,
+    hovered: bool,
+    highlight: usize,
+    scroll: f32,
+    search: String,
+    search_time: Instant,
+    selected: Option<usize>,
+}
+
+pub struct ComboBox<'a, T: 'a + Clone + PartialEq> {
+    value: T,
+    items: &'a [(T, &'a str)],
+    size: f32,
+    result: Option<T>,
+}
+
+struct Popup {
+    rect: Rect,
+    content: Rect,
+    item_height: f32,
+}
+
+impl<'a, T: 'a + Clone + PartialEq> ComboBox<'a, T> {
+    pub fn new(value: T, items: &'a [(T, &'a str)]) -> Self {
+        Self {
+            value,
+            items,
+            size: 16.0,
+            result: None,
+        }
+    }
+
+    pub fn with_text_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    fn text(&self, style: &Style, label: &str) -> Text {
+        Text {
+            text: label.to_string(),
+            font: style.font.clone(),
+            size: self.size,
+            wrap: TextWrap::NoWrap,
+            color: Color::black(),
+            border: None,
+            padding: Rect::zero(),
+        }
+    }
+
+    fn current_index(&self) -> Option<usize> {
+        self.items.iter().position(|&(ref value, _)| *value == self.value)
+    }
+
+    fn popup(&self, style: &Style, current: Rect, viewport: Rect) -> Popup {
+        let margin = style.popup.margin();
+        let item_height = self.text(style, "").measure(None).height() + 8.0;
+        let visible = self.items.len().min(MAX_VISIBLE_ITEMS) as f32;
+        let height = visible * item_height + margin.top + margin.bottom;
+
+        // open above the combo box if there is not enough room below it
+        let top = if current.bottom + height > viewport.bottom && current.top - height >= viewport.top {
+            current.top - height
+        } else {
+            current.bottom
+        };
+
+        let rect = Rect {
+            left: current.left,
+            top,
+            right: current.right,
+            bottom: top + height,
+        };
+
+        Popup {
+            rect,
+            content: rect.after_padding(margin),
+            item_height,
+        }
+    }
+
+    fn scroll_to(&self, state: &mut ComboBoxState, popup: &Popup, index: usize) {
+        let top = index as f32 * popup.item_height;
+        let bottom = top + popup.item_height;
+
+        if top < state.scroll {
+            state.scroll = top;
+        }
+        if bottom > state.scroll + popup.content.height() {
+            state.scroll = bottom - popup.content.height();
+        }
+    }
+
+    fn max_scroll(&self, popup: &Popup) -> f32 {
+        (self.items.len() as f32 * popup.item_height - popup.content.height()).max(0.0)
+    }
+
+    fn search(&self, state: &mut ComboBoxState, c: char) -> Option<usize> {
+        if state.search_time.elapsed() > Duration::from_millis(SEARCH_TIMEOUT) {
+            state.search.clear();
+        }
+        state.search_time = Instant::now();
+        state.search.extend(c.to_lowercase());
+
+        let search = &state.search;
+        self.items.iter().position(|&(_, label)| label.to_lowercase().starts_with(search.as_str()))
+    }
+}
+
+impl<'a, T: 'a + Clone + PartialEq> WidgetBase for ComboBox<'a, T> {
+    fn tabstop(&self) -> bool {
+        true
+    }
+
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let margin = style.button_normal.margin();
+
+        // size the combo box so the widest item fits
+        let content = self.items.iter().fold(self.text(style, "").measure(None), |acc, &(_, label)| {
+            let measured = self.text(style, label).measure(None);
+            Rect::from_wh(acc.width().max(measured.width()), acc.height().max(measured.height()))
+        });
+
+        let width = content.width() + margin.left + margin.right + style.combo_arrow.size.width() + 8.0;
+        let height = content.height() + margin.top + margin.bottom;
+
+        world.create_component(id, Layout::new()
+            .with_margins(margin)
+            .with_constraints(|layout| vec![
+                layout.width |GE(STRONG)| width as f64,
+                layout.height |GE(STRONG)| height as f64,
+            ]));
+        world.create_component(id, WidgetBackground::new(Background::Patch(style.button_normal.clone(), 1.0)));
+        world.create_component(id, Text {
+            padding: margin,
+            ..self.text(style, "")
+        });
+        world.create_component(id, Drawing::new());
+        world.create_component(id, Overlay::new());
+        world.create_component(id, ComboBoxState {
+            open: false,
+            hovered: false,
+            highlight: 0,
+            scroll: 0.0,
+            search: String::new(),
+            search_time: Instant::now(),
+            selected: None,
+        });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, _input: Option<Rect>) -> Option<Rect> {
+        let mut state = world.component::<ComboBoxState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        // selections are made in event(), so they are reported in the next frame.
+        // the items may have changed since then, a selection that is out of range is dropped
+        self.result = state.selected.take()
+            .and_then(|index| self.items.get(index))
+            .map(|&(ref value, _)| value.clone());
+        if let Some(ref value) = self.result {
+            self.value = value.clone();
+        }
+        state.highlight = state.highlight.min(self.items.len().saturating_sub(1));
+
+        let mut background = world.component::<WidgetBackground>(id).unwrap();
+        background.borrow_mut().normal = if state.open {
+            Background::Patch(style.button_pressed.clone(), 1.0)
+        } else if state.hovered {
+            Background::Patch(style.button_hover.clone(), 1.0)
+        } else {
+            Background::Patch(style.button_normal.clone(), 1.0)
+        };
+
+        let mut text = world.component::<Text>(id).unwrap();
+        let label = self.current_index().map_or("", |index| self.items[index].1);
+        if text.borrow().text != label {
+            text.borrow_mut().text = label.to_string();
+        }
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = layout.borrow().current;
+
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+        drawing.primitives.clear();
+
+        let mut overlay = world.component::<Overlay>(id).unwrap();
+        let mut overlay = overlay.borrow_mut();
+        overlay.primitives.clear();
+
+        if let Some(current) = current {
+            let content = current.after_padding(style.button_normal.margin());
+            let arrow = style.combo_arrow.size;
+            let x = content.right - arrow.width();
+            let y = ((content.top + content.bottom - arrow.height()) * 0.5).floor();
+
+            drawing.primitives.push(Primitive::DrawImage(
+                style.combo_arrow.clone(),
+                Rect::from_xywh(x, y, arrow.width(), arrow.height()),
+                Color::white()
+            ));
+
+            if state.open {
+                let popup = self.popup(style, current, world.viewport);
+                let first = (state.scroll / popup.item_height).floor() as usize;
+                let last = ((state.scroll + popup.content.height()) / popup.item_height).ceil() as usize;
+
+                overlay.primitives.push(Primitive::Draw9(style.popup.clone(), popup.rect, Color::white()));
+                overlay.primitives.push(Primitive::PushClip(popup.content));
+
+                for index in first..last.min(self.items.len()) {
+                    let top = popup.content.top + index as f32 * popup.item_height - state.scroll;
+                    let row = Rect {
+                        left: popup.content.left,
+                        top,
+                        right: popup.content.right,
+                        bottom: top + popup.item_height,
+                    };
+
+                    let mut text = self.text(style, self.items[index].1);
+                    if index == state.highlight {
+                        overlay.primitives.push(Primitive::DrawRect(row, Color { r: 0.2, g: 0.4, b: 0.8, a: 1.0 }));
+                        text.color = Color::white();
+                    }
+                    overlay.primitives.push(Primitive::DrawText(text, row.after_padding(Rect {
+                        left: 4.0,
+                        top: 4.0,
+                        right: 4.0,
+                        bottom: 4.0
+                    })));
+                }
+
+                // show a scroll bar if not all items fit
+                let total = self.items.len() as f32 * popup.item_height;
+                if total > popup.content.height() {
+                    let width = style.scroll_vertical.0.image.size.width();
+                    let bar = Rect {
+                        left: popup.content.right - width,
+                        top: popup.content.top,
+                        right: popup.content.right,
+                        bottom: popup.content.bottom,
+                    };
+                    let handle = Rect {
+                        top: bar.top + bar.height() * state.scroll / total,
+                        bottom: bar.top + bar.height() * (state.scroll + popup.content.height()) / total,
+                        ..bar
+                    };
+                    overlay.primitives.push(Primitive::Draw9(style.scroll_vertical.0.clone(), bar, Color::white()));
+                    overlay.primitives.push(Primitive::Draw9(style.scroll_vertical.1.clone(), handle, Color::white()));
+                }
+
+                overlay.primitives.push(Primitive::PopClip);
+            }
+        }
+
+        None
+    }
+
+    fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        let mut state = world.component::<ComboBoxState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = match layout.borrow().current {
+            Some(current) => current,
+            None => return,
+        };
+
+        let popup = self.popup(style, current, world.viewport);
+
+        state.hovered = context.cursor.inside(&current);
+
+        if !state.open {
+            let open = match context.event {
+                Event::Press(Key::LeftMouseButton, _) => state.hovered,
+                Event::Press(Key::Enter, _) |
+                Event::Press(Key::Space, _) => context.focused,
+                _ => false,
+            };
+
+            if open {
+                let highlight = self.current_index().unwrap_or(0);
+                state.open = true;
+                state.highlight = highlight;
+                state.scroll = 0.0;
+                self.scroll_to(&mut state, &popup, highlight);
+                context.capture = Capture::CaptureFocus(MouseStyle::Arrow);
+            } else if context.focused {
+                // change the selection without opening the popup
+                let count = self.items.len();
+                let index = self.current_index();
+                state.selected = match context.event {
+                    Event::Press(Key::Up, _) if count > 0 =>
+                        Some(index.map_or(0, |i| if i > 0 { i - 1 } else { 0 })),
+                    Event::Press(Key::Down, _) if count > 0 =>
+                        Some(index.map_or(0, |i| (i + 1).min(count - 1))),
+                    Event::Text(c) if !c.is_control() && (c != ' ' || !state.search.is_empty()) =>
+                        self.search(&mut state, c),
+                    _ => state.selected,
+                };
+            }
+
+            return;
+        }
+
+        // the popup escapes the clipping of the parents, so use the full viewport for hit tests
+        let cursor = context.cursor.expand(&world.viewport);
+        let hovered_item = if cursor.inside(&popup.content) {
+            let index = ((cursor.y - popup.content.top + state.scroll) / popup.item_height).floor();
+            if index >= 0.0 && (index as usize) < self.items.len() {
+                Some(index as usize)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        let mut close = false;
+
+        match context.event {
+            Event::Cursor(_, _) => {
+                hovered_item.map(|index| state.highlight = index);
+            },
+            Event::Press(Key::LeftMouseButton, _) => {
+                if let Some(index) = hovered_item {
+                    state.selected = Some(index);
+                }
+                close = !cursor.inside(&popup.rect) || hovered_item.is_some();
+            },
+            Event::Scroll(_, dy) => {
+                if cursor.inside(&popup.rect) {
+                    state.scroll = (state.scroll - dy).max(0.0).min(self.max_scroll(&popup));
+                }
+            },
+            Event::Press(Key::Up, _) => {
+                state.highlight = if state.highlight > 0 { state.highlight - 1 } else { 0 };
+                let highlight = state.highlight;
+                self.scroll_to(&mut state, &popup, highlight);
+            },
+            Event::Press(Key::Down, _) => {
+                state.highlight = (state.highlight + 1).min(self.items.len().max(1) - 1);
+                let highlight = state.highlight;
+                self.scroll_to(&mut state, &popup, highlight);
+            },
+            Event::Press(Key::Home, _) => {
+                state.highlight = 0;
+                state.scroll = 0.0;
+            },
+            Event::Press(Key::End, _) => {
+                state.highlight = self.items.len().max(1) - 1;
+                state.scroll = self.max_scroll(&popup);
+            },
+            Event::Press(Key::Enter, _) => {
+                if state.highlight < self.items.len() {
+                    state.selected = Some(state.highlight);
+                }
+                close = true;
+            },
+            Event::Press(Key::Escape, _) |
+            Event::Press(Key::Tab, _) => {
+                close = true;
+            },
+            Event::Text(c) if !c.is_control() && (c != ' ' || !state.search.is_empty()) => {
+                if let Some(index) = self.search(&mut state, c) {
+                    state.highlight = index;
+                    self.scroll_to(&mut state, &popup, index);
+                }
+            },
+            _ => (),
+        }
+
+        if close {
+            state.open = false;
+            state.search.clear();
+        } else {
+            context.capture = Capture::CaptureFocus(MouseStyle::Arrow);
+        }
+    }
+}
+
+impl<'a, T: 'a + Clone + PartialEq> Widget for ComboBox<'a, T> {
+    type Result = Option<T>;
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result {
+        self.result.clone()
+    }
+}
@@ -0,0 +1,165 @@
+use super::*;
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+
+// Space around and between the parts of a dialog
+const DIALOG_SPACING: f32 = 16.0;
+const DIALOG_MIN_WIDTH: f32 = 256.0;
+const DIALOG_BUTTON_SIZE: (f32, f32) = (96.0, 32.0);
+// Dims everything behind a dialog
+const BACKDROP: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 0.5 };
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum DialogResult {
+    // The dialog is still open
+    Open,
+    Ok,
+    Cancel,
+}
+
+impl Ui {
+    // Show a message with an OK button. Dialogs are shown in a `Layer::Modal` layer with the
+    //  given id for as long as they are called every frame, the result tells when the dialog
+    //  was closed. Escape closes a dialog with `DialogResult::Cancel`.
+    pub fn message_box(&mut self, style: &Style, id: &str, title: &str, text: &str) -> DialogResult {
+        self.dialog(style, id, title, text, None, false)
+    }
+
+    // Ask a question that can be answered with OK or Cancel
+    pub fn confirm(&mut self, style: &Style, id: &str, title: &str, text: &str) -> DialogResult {
+        self.dialog(style, id, title, text, None, true)
+    }
+
+    // Ask for a line of text. `value` is edited while the dialog is open.
+    pub fn prompt(&mut self, style: &Style, id: &str, title: &str, text: &str, value: &mut String) -> DialogResult {
+        self.dialog(style, id, title, text, Some(value), true)
+    }
+
+    fn dialog(&mut self, style: &Style, id: &str, title: &str, text: &str, value: Option<&mut String>, cancel: bool) -> DialogResult {
+        let escape = self.events.iter().any(|event| match *event {
+            Event::Press(Key::Escape, _) => true,
+            _ => false,
+        });
+        let enter = self.events.iter().any(|event| match *event {
+            Event::Press(Key::Enter, _) => true,
+            _ => false,
+        });
+
+        let mut result = DialogResult::Open;
+        let mut input_id = None;
+        let mut ok_id = None;
+        let mut cancel_id = None;
+
+        {
+            let mut ui = self.layer(style, id, Layer::Modal);
+            let is_new = ui.is_new;
+
+            // the backdrop covers the viewport, so nothing behind the dialog can be clicked
+            ui.add("backdrop", Frame::new(Background::Color(BACKDROP)));
+
+            {
+                let window = Window::new(Rect::from_wh(DIALOG_MIN_WIDTH, 0.0), true)
+                    .with_title(title)
+                    .closable()
+                    .auto_sized()
+                    .centered();
+
+                let mut window = ui.add("dialog", window);
+                if !window.result {
+                    result = DialogResult::Cancel;
+                }
+
+                window.with(|ui| {
+                    let has_input = value.is_some();
+
+                    ui.add("text", Label::simple(text));
+
+                    if let Some(value) = value {
+                        let input = ui.add("input", Input::new(value));
+                        input_id = Some(input.internal_id);
+                        if input.result {
+                            result = DialogResult::Ok;
+                        }
+                    }
+
+                    let ok = ui.add("ok", Button::new().with_size(DIALOG_BUTTON_SIZE));
+                    ok_id = Some(ok.internal_id);
+                    if ok.wrap(Label::simple("OK")) {
+                        result = DialogResult::Ok;
+                    }
+
+                    if cancel {
+                        let cancel = ui.add("cancel", Button::new().with_size(DIALOG_BUTTON_SIZE));
+                        cancel_id = Some(cancel.internal_id);
+                        if cancel.wrap(Label::simple("Cancel")) {
+                            result = DialogResult::Cancel;
+                        }
+                    }
+
+                    // the buttons are at the bottom right, below the text and input
+                    ui.rules(|var| {
+                        let spacing = DIALOG_SPACING as f64;
+                        let above_buttons = if has_input { var("input.bottom") } else { var("text.bottom") };
+
+                        let mut rules = vec![
+                            var("text.left") |EQ(REQUIRED)| var("super.margin_left") + spacing,
+                            var("text.right") |LE(REQUIRED)| var("super.margin_right") - spacing,
+                            var("text.top") |EQ(REQUIRED)| var("super.margin_top") + spacing,
+                            var("ok.left") |GE(REQUIRED)| var("super.margin_left") + spacing,
+                            var("ok.top") |EQ(REQUIRED)| above_buttons + spacing,
+                            var("ok.bottom") |EQ(REQUIRED)| var("super.margin_bottom") - spacing,
+                        ];
+
+                        if has_input {
+                            rules.push(var("input.left") |EQ(REQUIRED)| var("text.left"));
+                            rules.push(var("input.right") |EQ(REQUIRED)| var("super.margin_right") - spacing);
+                            rules.push(var("input.top") |EQ(REQUIRED)| var("text.bottom") + spacing * 0.5);
+                        }
+
+                        if cancel {
+                            rules.push(var("cancel.top") |EQ(REQUIRED)| var("ok.top"));
+                            rules.push(var("cancel.right") |EQ(REQUIRED)| var("super.margin_right") - spacing);
+                            rules.push(var("ok.right") |EQ(REQUIRED)| var("cancel.left") - spacing * 0.5);
+                        } else {
+                            rules.push(var("ok.right") |EQ(REQUIRED)| var("super.margin_right") - spacing);
+                        }
+
+                        rules
+                    });
+                });
+            }
+
+            layout_rules!(ui,
+                (backdrop.left = super.left),
+                (backdrop.right = super.right),
+                (backdrop.top = super.top),
+                (backdrop.bottom = super.bottom),
+            );
+
+            // a new dialog takes the focus, so it's buttons can be reached with tab
+            if is_new {
+                ui.parent.focus = input_id.or(ok_id);
+            }
+        }
+
+        if result == DialogResult::Open {
+            // enter clicks the focused button. the input handles enter by itself.
+            let input_focused = input_id.map_or(false, |input| self.focus == Some(input));
+            if escape {
+                result = DialogResult::Cancel;
+            } else if enter && !input_focused {
+                result = if cancel_id.is_some() && self.focus == cancel_id {
+                    DialogResult::Cancel
+                } else {
+                    DialogResult::Ok
+                };
+            }
+        }
+
+        if result != DialogResult::Open {
+            self.focus = None;
+        }
+
+        result
+    }
+}
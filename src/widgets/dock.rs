@@ -0,0 +1,766 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use std::fmt;
+use std::str::FromStr;
+use super::*;
+
+// Size of a window that is floated before it was ever laid out
+const FLOAT_SIZE: (f32, f32) = (256.0, 192.0);
+// Offset of the cursor from the top left corner of a window that is dragged out of the dock
+const FLOAT_GRAB: (f32, f32) = (32.0, 12.0);
+// Share of a dock area at each edge that docks a window next to the area
+const EDGE_SHARE: f32 = 0.25;
+// Size of the target in the center of the viewport when no window is docked
+const EMPTY_TARGET: f32 = 96.0;
+
+#[derive(Clone,Debug,PartialEq)]
+pub enum DockNode {
+    // Windows that share an area as tabs, with the selected window
+    Tabs(Vec<String>, usize),
+    // Two nodes next to or above each other, with the share of the first node
+    Split(SplitDirection, f32, Box<DockNode>, Box<DockNode>),
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum DockSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    // Add the window to the tabs of the area
+    Center,
+}
+
+// The arrangement of the windows of a `Ui::dock`. It can be saved with `to_string()` and
+//  restored with `parse()`.
+#[derive(Clone,Debug)]
+pub struct DockLayout {
+    pub root: Option<DockNode>,
+    // Windows that are not docked, with the rect of the window
+    pub floating: Vec<(String, Rect)>,
+    // The tabs areas of the last frame, with the path to their node
+    areas: Vec<(Vec<usize>, Rect)>,
+    // A window that was just dragged out of the dock, it keeps following the cursor
+    dragged: Option<String>,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct ParseDockLayoutError;
+
+impl DockNode {
+    // All windows in this node, in order
+    pub fn windows(&self) -> Vec<&str> {
+        match *self {
+            DockNode::Tabs(ref windows, _) => windows.iter().map(|w| w.as_str()).collect(),
+            DockNode::Split(_, _, ref first, ref second) => {
+                let mut windows = first.windows();
+                windows.extend(second.windows());
+                windows
+            },
+        }
+    }
+
+    // The node without the window, or `None` if nothing is left
+    fn without(self, window: &str) -> Option<DockNode> {
+        match self {
+            DockNode::Tabs(mut windows, selected) => {
+                windows.retain(|w| w != window);
+                if windows.is_empty() {
+                    None
+                } else {
+                    let selected = selected.min(windows.len() - 1);
+                    Some(DockNode::Tabs(windows, selected))
+                }
+            },
+            DockNode::Split(direction, ratio, first, second) => {
+                match (first.without(window), second.without(window)) {
+                    (Some(first), Some(second)) => Some(DockNode::Split(direction, ratio, Box::new(first), Box::new(second))),
+                    (Some(node), None) | (None, Some(node)) => Some(node),
+                    (None, None) => None,
+                }
+            },
+        }
+    }
+
+    fn insert(&mut self, path: &[usize], window: String, side: DockSide) {
+        if let Some((&first, rest)) = path.split_first() {
+            if let DockNode::Split(_, _, ref mut a, ref mut b) = *self {
+                let child = if first == 0 { a } else { b };
+                child.insert(rest, window, side);
+                return;
+            }
+        }
+
+        if side == DockSide::Center {
+            if let DockNode::Tabs(ref mut windows, ref mut selected) = *self {
+                windows.push(window);
+                *selected = windows.len() - 1;
+                return;
+            }
+        }
+
+        let (direction, before) = match side {
+            DockSide::Left => (SplitDirection::Horizontal, true),
+            DockSide::Right | DockSide::Center => (SplitDirection::Horizontal, false),
+            DockSide::Top => (SplitDirection::Vertical, true),
+            DockSide::Bottom => (SplitDirection::Vertical, false),
+        };
+
+        let old = ::std::mem::replace(self, DockNode::Tabs(vec![], 0));
+        let new = DockNode::Tabs(vec![window], 0);
+        *self = if before {
+            DockNode::Split(direction, 0.5, Box::new(new), Box::new(old))
+        } else {
+            DockNode::Split(direction, 0.5, Box::new(old), Box::new(new))
+        };
+    }
+
+    // Widgets are keyed by the structure of the node, so the state of a splitter is not reused
+    //  for a different split.
+    fn key(&self) -> String {
+        match *self {
+            DockNode::Tabs(_, _) => "tabs".to_string(),
+            DockNode::Split(direction, _, ref first, ref second) => format!("split {:?} {} {}",
+                direction,
+                first.windows().join("|"),
+                second.windows().join("|")
+            ),
+        }
+    }
+}
+
+impl DockLayout {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            floating: vec![],
+            areas: vec![],
+            dragged: None,
+        }
+    }
+
+    pub fn with_root(mut self, root: DockNode) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    pub fn with_floating(mut self, window: &str, rect: Rect) -> Self {
+        self.floating.push((window.to_string(), rect));
+        self
+    }
+
+    pub fn contains(&self, window: &str) -> bool {
+        self.floating.iter().any(|&(ref w, _)| w == window) ||
+        self.root.as_ref().map_or(false, |root| root.windows().contains(&window))
+    }
+
+    // Remove a window from the layout, wherever it is
+    pub fn remove(&mut self, window: &str) {
+        self.floating.retain(|&(ref w, _)| w != window);
+        self.root = self.root.take().and_then(|root| root.without(window));
+        if self.dragged.as_ref().map_or(false, |w| w == window) {
+            self.dragged = None;
+        }
+    }
+
+    // Dock a window at a side of the node at `path`. The path holds the branch to take (0 for
+    //  the first, 1 for the second node) at every split from the root, after the window is
+    //  removed from it's old place.
+    pub fn dock(&mut self, window: &str, path: &[usize], side: DockSide) {
+        self.remove(window);
+        match self.root {
+            Some(ref mut root) => root.insert(path, window.to_string(), side),
+            None => self.root = Some(DockNode::Tabs(vec![window.to_string()], 0)),
+        }
+    }
+
+    pub fn float(&mut self, window: &str, rect: Rect) {
+        self.remove(window);
+        self.floating.push((window.to_string(), rect));
+    }
+
+    // The dock target under the cursor, with the rect the window would get
+    fn target(&self, viewport: Rect, x: f32, y: f32) -> Option<(Vec<usize>, DockSide, Rect)> {
+        if self.root.is_none() {
+            let (cx, cy) = ((viewport.left + viewport.right) * 0.5, (viewport.top + viewport.bottom) * 0.5);
+            let near = (x - cx).abs() < EMPTY_TARGET * 0.5 && (y - cy).abs() < EMPTY_TARGET * 0.5;
+            return if near {
+                Some((vec![], DockSide::Center, viewport))
+            } else {
+                None
+            };
+        }
+
+        self.areas.iter().find(|&&(_, rect)| {
+            x >= rect.left && x < rect.right && y >= rect.top && y < rect.bottom
+        }).map(|&(ref path, rect)| {
+            let u = (x - rect.left) / rect.width();
+            let v = (y - rect.top) / rect.height();
+            let (w, h) = (rect.width() * 0.5, rect.height() * 0.5);
+
+            let (side, preview) = if u < EDGE_SHARE {
+                (DockSide::Left, Rect { right: rect.left + w, ..rect })
+            } else if u > 1.0 - EDGE_SHARE {
+                (DockSide::Right, Rect { left: rect.right - w, ..rect })
+            } else if v < EDGE_SHARE {
+                (DockSide::Top, Rect { bottom: rect.top + h, ..rect })
+            } else if v > 1.0 - EDGE_SHARE {
+                (DockSide::Bottom, Rect { top: rect.bottom - h, ..rect })
+            } else {
+                (DockSide::Center, rect)
+            };
+
+            (path.clone(), side, preview)
+        })
+    }
+}
+
+fn write_name(f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+    write!(f, "\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl fmt::Display for DockNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DockNode::Tabs(ref windows, selected) => {
+                write!(f, "(tabs {}", selected)?;
+                for window in windows.iter() {
+                    write!(f, " ")?;
+                    write_name(f, window)?;
+                }
+                write!(f, ")")
+            },
+            DockNode::Split(direction, ratio, ref first, ref second) => {
+                let direction = match direction {
+                    SplitDirection::Horizontal => "horizontal",
+                    SplitDirection::Vertical => "vertical",
+                };
+                write!(f, "(split {} {} {} {})", direction, ratio, first, second)
+            },
+        }
+    }
+}
+
+impl fmt::Display for DockLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref root) = self.root {
+            writeln!(f, "dock {}", root)?;
+        }
+        for &(ref window, rect) in self.floating.iter() {
+            write!(f, "float ")?;
+            write_name(f, window)?;
+            writeln!(f, " {} {} {} {}", rect.left, rect.top, rect.right, rect.bottom)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone,Debug,PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+    Name(String),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ParseDockLayoutError> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '"' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => name.push(chars.next().ok_or(ParseDockLayoutError)?),
+                        Some(c) => name.push(c),
+                        None => return Err(ParseDockLayoutError),
+                    }
+                }
+                tokens.push(Token::Name(name));
+            },
+            c if c.is_whitespace() => (),
+            c => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            },
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: ::std::vec::IntoIter<Token>,
+}
+
+impl Parser {
+    fn next(&mut self) -> Result<Token, ParseDockLayoutError> {
+        self.tokens.next().ok_or(ParseDockLayoutError)
+    }
+
+    fn number<T: FromStr>(&mut self) -> Result<T, ParseDockLayoutError> {
+        match self.next()? {
+            Token::Word(word) => word.parse().map_err(|_| ParseDockLayoutError),
+            _ => Err(ParseDockLayoutError),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, ParseDockLayoutError> {
+        match self.next()? {
+            Token::Name(name) => Ok(name),
+            _ => Err(ParseDockLayoutError),
+        }
+    }
+
+    fn node(&mut self) -> Result<DockNode, ParseDockLayoutError> {
+        if self.next()? != Token::Open {
+            return Err(ParseDockLayoutError);
+        }
+
+        match self.next()? {
+            Token::Word(ref word) if word == "tabs" => {
+                let selected = self.number()?;
+                let mut windows = vec![];
+                loop {
+                    match self.next()? {
+                        Token::Name(name) => windows.push(name),
+                        Token::Close => break,
+                        _ => return Err(ParseDockLayoutError),
+                    }
+                }
+                if windows.is_empty() {
+                    return Err(ParseDockLayoutError);
+                }
+                let selected = ::std::cmp::min(selected, windows.len() - 1);
+                Ok(DockNode::Tabs(windows, selected))
+            },
+            Token::Word(ref word) if word == "split" => {
+                let direction = match self.next()? {
+                    Token::Word(ref word) if word == "horizontal" => SplitDirection::Horizontal,
+                    Token::Word(ref word) if word == "vertical" => SplitDirection::Vertical,
+                    _ => return Err(ParseDockLayoutError),
+                };
+                let ratio: f32 = self.number()?;
+                let first = self.node()?;
+                let second = self.node()?;
+                if self.next()? != Token::Close {
+                    return Err(ParseDockLayoutError);
+                }
+                Ok(DockNode::Split(direction, ratio.max(0.0).min(1.0), Box::new(first), Box::new(second)))
+            },
+            _ => Err(ParseDockLayoutError),
+        }
+    }
+}
+
+impl FromStr for DockLayout {
+    type Err = ParseDockLayoutError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { tokens: tokenize(text)?.into_iter() };
+        let mut layout = DockLayout::new();
+
+        while let Some(token) = parser.tokens.next() {
+            match token {
+                Token::Word(ref word) if word == "dock" => {
+                    layout.root = Some(parser.node()?);
+                },
+                Token::Word(ref word) if word == "float" => {
+                    let window = parser.name()?;
+                    let left = parser.number()?;
+                    let top = parser.number()?;
+                    let right = parser.number()?;
+                    let bottom = parser.number()?;
+                    layout.floating.push((window, Rect { left, top, right, bottom }));
+                },
+                _ => return Err(ParseDockLayoutError),
+            }
+        }
+
+        Ok(layout)
+    }
+}
+
+impl Ui {
+    // Show the windows of a dock layout. The docked windows fill the viewport in a `Layer::Back`
+    //  layer with the given id, floating windows get a `Layer::Normal` layer each.
+    // Floating windows can be docked by dragging them over a docked area, docked windows are
+    //  floated again by dragging their tab away. `f` is called with the name of every window
+    //  that is shown, to add the content of the window. The content is laid out relative to
+    //  the window with `super`.
+    pub fn dock<F: FnMut(&mut Context, &str)>(&mut self, style: &Style, id: &str, layout: &mut DockLayout, mut f: F) {
+        let released = self.events.iter().any(|event| match *event {
+            Event::Release(Key::LeftMouseButton, _) => true,
+            _ => false,
+        });
+        let (x, y) = self.cursor;
+
+        // a floating window that is dragged over a docked area is docked when it's released
+        let mut preview = None;
+        let mut drop = None;
+        for &(ref window, _) in layout.floating.iter() {
+            let layer = floating_layer(id, window);
+            let state = self.find_widget(&[&layer[..], "window"])
+                .and_then(|w| self.component::<WindowState>(w));
+            let mut state = match state {
+                Some(state) => state,
+                None => continue,
+            };
+
+            let dragged = match *state.borrow() {
+                WindowState::Drag(_, _) => true,
+                _ => false,
+            };
+
+            if dragged {
+                let target = layout.target(self.viewport, x, y);
+                if released {
+                    *state.borrow_mut() = WindowState::Idle;
+                    drop = target.map(|(path, side, _)| (window.clone(), path, side));
+                } else {
+                    preview = target.map(|(_, _, rect)| rect);
+                }
+                break;
+            }
+        }
+
+        if let Some((window, path, side)) = drop {
+            layout.dock(&window, &path, side);
+        }
+
+        // the docked windows
+        let mut areas = vec![];
+        let mut detached = None;
+        {
+            let mut ui = self.layer(style, id, Layer::Back);
+            if let Some(ref mut root) = layout.root {
+                let mut path = vec![];
+                dock_node(&mut ui, root, &mut path, &mut areas, &mut detached, &mut f);
+            }
+        }
+        layout.areas = areas;
+
+        if let Some((window, rect)) = detached {
+            let rect = Rect::from_xywh(
+                x - FLOAT_GRAB.0,
+                y - FLOAT_GRAB.1,
+                rect.width().max(FLOAT_SIZE.0 * 0.5),
+                rect.height().max(FLOAT_SIZE.1 * 0.5)
+            );
+            layout.float(&window, rect);
+            layout.dragged = Some(window);
+        }
+
+        // the floating windows
+        for &mut (ref window, ref mut rect) in layout.floating.iter_mut() {
+            let layer = floating_layer(id, window);
+            let mut ui = self.layer(style, &layer, Layer::Normal);
+            let mut w = ui.add("window", Window::new(Rect::from_wh(FLOAT_SIZE.0 * 0.5, FLOAT_SIZE.1 * 0.5), true));
+
+            let window_layout = w.context.parent.component::<Layout>(w.internal_id).unwrap();
+            if w.context.is_new {
+                let window_layout = window_layout.borrow();
+                let solver = &mut w.context.parent.layout_solver;
+                solver.suggest_value(window_layout.left, rect.left as f64).ok();
+                solver.suggest_value(window_layout.top, rect.top as f64).ok();
+                solver.suggest_value(window_layout.width, rect.width() as f64).ok();
+                solver.suggest_value(window_layout.height, rect.height() as f64).ok();
+
+                // a window that was dragged out of the dock keeps following the cursor
+                if layout.dragged.as_ref() == Some(window) {
+                    let mut state = w.context.parent.component::<WindowState>(w.internal_id).unwrap();
+                    *state.borrow_mut() = WindowState::Drag(FLOAT_GRAB.0, FLOAT_GRAB.1);
+                }
+            } else if let Some(current) = window_layout.borrow().current {
+                *rect = current;
+            }
+
+            f(&mut w.context, window);
+        }
+        layout.dragged = None;
+
+        if let Some(preview) = preview {
+            let mut overlay = self.overlay.borrow_mut();
+            overlay.push(Primitive::DrawRect(preview, Color { r: 0.2, g: 0.4, b: 0.8, a: 0.3 }));
+        }
+    }
+}
+
+fn floating_layer(id: &str, window: &str) -> String {
+    format!("{} {}", id, window)
+}
+
+// Add the widgets for a docked node that fills the parent of `ui`
+fn dock_node(
+    ui: &mut Context,
+    node: &mut DockNode,
+    path: &mut Vec<usize>,
+    areas: &mut Vec<(Vec<usize>, Rect)>,
+    detached: &mut Option<(String, Rect)>,
+    f: &mut FnMut(&mut Context, &str)
+) {
+    let edges = (
+        ui.parent.var("super.left"),
+        ui.parent.var("super.top"),
+        ui.parent.var("super.right"),
+        ui.parent.var("super.bottom"),
+    );
+    let mut panel = ui.add(&node.key(), DockPanel { edges });
+
+    match *node {
+        DockNode::Tabs(ref mut windows, ref mut selected) => {
+            let (result, rect) = {
+                let tabs = panel.context.add("tabs", Tabs::new().detachable());
+                let rect = tabs.context.parent.component::<Layout>(tabs.internal_id).unwrap().borrow().current;
+                let list: Vec<Tab> = windows.iter().map(|window| Tab::new(window)).collect();
+                let result = tabs.pages(&list, *selected, |ui, index| f(ui, &windows[index]));
+                (result, rect)
+            };
+
+            if let Some(rect) = rect {
+                areas.push((path.clone(), rect));
+            }
+
+            if let Some(index) = result.detached {
+                *detached = Some((windows[index].clone(), rect.unwrap_or(Rect::zero())));
+            }
+            if let Some((from, to)) = result.moved {
+                let window = windows.remove(from);
+                windows.insert(to, window);
+            }
+            *selected = result.selected;
+
+            panel.context.rules(|var| vec![
+                var("tabs.left") |EQ(REQUIRED)| var("super.left"),
+                var("tabs.top") |EQ(REQUIRED)| var("super.top"),
+                var("tabs.right") |EQ(REQUIRED)| var("super.right"),
+                var("tabs.bottom") |EQ(REQUIRED)| var("super.bottom"),
+            ]);
+        },
+        DockNode::Split(direction, ref mut ratio, ref mut first, ref mut second) => {
+            let splitter = Splitter::new(direction, 2).with_ratios(&[*ratio, 1.0 - *ratio]);
+            let split = panel.context.add("split", splitter);
+            let ratios = split.result.clone();
+
+            split.panes(|ui, index| {
+                path.push(index);
+                let child = if index == 0 { &mut **first } else { &mut **second };
+                dock_node(ui, child, path, areas, detached, f);
+                path.pop();
+            });
+
+            if let Some(&share) = ratios.first() {
+                *ratio = share;
+            }
+
+            panel.context.rules(|var| vec![
+                var("split.left") |EQ(REQUIRED)| var("super.left"),
+                var("split.top") |EQ(REQUIRED)| var("super.top"),
+                var("split.right") |EQ(REQUIRED)| var("super.right"),
+                var("split.bottom") |EQ(REQUIRED)| var("super.bottom"),
+            ]);
+        },
+    }
+}
+
+// Fills the parent of a docked node. The node widgets are keyed by the structure of the node,
+//  so the constraints are made in `create()` instead of with layout rules of the parent.
+struct DockPanel {
+    edges: (cassowary::Variable, cassowary::Variable, cassowary::Variable, cassowary::Variable),
+}
+
+impl WidgetBase for DockPanel {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, _style: &Style) {
+        let (left, top, right, bottom) = self.edges;
+        world.create_component(id, Layout::new().with_constraints(|layout| vec![
+            layout.left |EQ(REQUIRED)| left,
+            layout.top |EQ(REQUIRED)| top,
+            layout.right |EQ(REQUIRED)| right,
+            layout.bottom |EQ(REQUIRED)| bottom,
+        ]));
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let layout = world.component::<Layout>(id).unwrap();
+        let layout = layout.borrow();
+        layout.current().and_then(|current| input.and_then(|ir| ir.intersect(current)))
+    }
+}
+
+impl Widget for DockPanel {
+    type Result = ();
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result { }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tabs(windows: &[&str], selected: usize) -> DockNode {
+        DockNode::Tabs(windows.iter().map(|w| w.to_string()).collect(), selected)
+    }
+
+    #[test]
+    fn layout_round_trips_through_string() {
+        let root = DockNode::Split(
+            SplitDirection::Horizontal,
+            0.25,
+            Box::new(tabs(&["files", "say \"hi\""], 1)),
+            Box::new(DockNode::Split(
+                SplitDirection::Vertical,
+                0.7,
+                Box::new(tabs(&["C:\\temp\\", "(editor)"], 0)),
+                Box::new(tabs(&["log \\\"quoted\\\""], 0)),
+            )),
+        );
+        let layout = DockLayout::new()
+            .with_root(root)
+            .with_floating("float \"one\"", Rect { left: 10.0, top: 20.5, right: 300.0, bottom: 240.25 })
+            .with_floating("back\\slash", Rect::from_xywh(-5.0, 0.0, 64.0, 48.0));
+
+        let text = layout.to_string();
+        let parsed: DockLayout = text.parse().unwrap();
+
+        assert_eq!(parsed.root, layout.root);
+        assert_eq!(parsed.floating.len(), layout.floating.len());
+        for (parsed, original) in parsed.floating.iter().zip(layout.floating.iter()) {
+            assert_eq!(parsed.0, original.0);
+            let (a, b) = (parsed.1, original.1);
+            assert_eq!((a.left, a.top, a.right, a.bottom), (b.left, b.top, b.right, b.bottom));
+        }
+        assert_eq!(parsed.to_string(), text);
+    }
+
+    #[test]
+    fn insert_follows_the_path_and_splits_at_the_side() {
+        let mut root = DockNode::Split(
+            SplitDirection::Horizontal,
+            0.3,
+            Box::new(tabs(&["a"], 0)),
+            Box::new(tabs(&["b"], 0)),
+        );
+
+        root.insert(&[0], "c".to_string(), DockSide::Center);
+        root.insert(&[1], "d".to_string(), DockSide::Top);
+        root.insert(&[1, 1], "e".to_string(), DockSide::Right);
+
+        assert_eq!(root, DockNode::Split(
+            SplitDirection::Horizontal,
+            0.3,
+            Box::new(tabs(&["a", "c"], 1)),
+            Box::new(DockNode::Split(
+                SplitDirection::Vertical,
+                0.5,
+                Box::new(tabs(&["d"], 0)),
+                Box::new(DockNode::Split(
+                    SplitDirection::Horizontal,
+                    0.5,
+                    Box::new(tabs(&["b"], 0)),
+                    Box::new(tabs(&["e"], 0)),
+                )),
+            )),
+        ));
+
+        let mut root = tabs(&["a"], 0);
+        root.insert(&[], "b".to_string(), DockSide::Left);
+        assert_eq!(root, DockNode::Split(
+            SplitDirection::Horizontal,
+            0.5,
+            Box::new(tabs(&["b"], 0)),
+            Box::new(tabs(&["a"], 0)),
+        ));
+
+        let mut root = tabs(&["a"], 0);
+        root.insert(&[], "b".to_string(), DockSide::Bottom);
+        assert_eq!(root, DockNode::Split(
+            SplitDirection::Vertical,
+            0.5,
+            Box::new(tabs(&["a"], 0)),
+            Box::new(tabs(&["b"], 0)),
+        ));
+    }
+
+    #[test]
+    fn without_collapses_empty_nodes() {
+        let root = DockNode::Split(
+            SplitDirection::Vertical,
+            0.5,
+            Box::new(tabs(&["a", "b", "c"], 2)),
+            Box::new(DockNode::Split(
+                SplitDirection::Horizontal,
+                0.5,
+                Box::new(tabs(&["d"], 0)),
+                Box::new(tabs(&["e"], 0)),
+            )),
+        );
+
+        // the selected tab stays in range
+        let root = root.without("c").unwrap();
+        assert_eq!(root.windows(), vec!["a", "b", "d", "e"]);
+        match root {
+            DockNode::Split(_, _, ref first, _) => assert_eq!(**first, tabs(&["a", "b"], 1)),
+            _ => panic!("expected a split"),
+        }
+
+        // a split with one empty side is replaced by the other side
+        let root = root.without("d").unwrap();
+        assert_eq!(root, DockNode::Split(
+            SplitDirection::Vertical,
+            0.5,
+            Box::new(tabs(&["a", "b"], 1)),
+            Box::new(tabs(&["e"], 0)),
+        ));
+
+        assert_eq!(root.clone().without("missing"), Some(root.clone()));
+
+        let root = root.without("e").unwrap();
+        assert_eq!(root, tabs(&["a", "b"], 1));
+        assert_eq!(root.without("a").and_then(|root| root.without("b")), None);
+    }
+
+    #[test]
+    fn target_picks_the_side_by_the_edge_under_the_cursor() {
+        let viewport = Rect::from_wh(400.0, 200.0);
+        let area = Rect::from_xywh(100.0, 0.0, 200.0, 100.0);
+        let mut layout = DockLayout::new().with_root(tabs(&["a"], 0));
+        layout.areas = vec![(vec![1, 0], area)];
+
+        let side = |x, y| layout.target(viewport, x, y).map(|(path, side, preview)| {
+            assert_eq!(path, vec![1, 0]);
+            (side, (preview.left, preview.top, preview.right, preview.bottom))
+        });
+
+        assert_eq!(side(110.0, 50.0), Some((DockSide::Left, (100.0, 0.0, 200.0, 100.0))));
+        assert_eq!(side(290.0, 50.0), Some((DockSide::Right, (200.0, 0.0, 300.0, 100.0))));
+        assert_eq!(side(200.0, 10.0), Some((DockSide::Top, (100.0, 0.0, 300.0, 50.0))));
+        assert_eq!(side(200.0, 90.0), Some((DockSide::Bottom, (100.0, 50.0, 300.0, 100.0))));
+        assert_eq!(side(200.0, 50.0), Some((DockSide::Center, (100.0, 0.0, 300.0, 100.0))));
+        // the left and right edges win in the corners
+        assert_eq!(side(110.0, 5.0).map(|(side, _)| side), Some(DockSide::Left));
+        assert_eq!(side(50.0, 50.0), None);
+        assert_eq!(side(200.0, 150.0), None);
+    }
+
+    #[test]
+    fn target_of_an_empty_dock_is_the_center_of_the_viewport() {
+        let viewport = Rect::from_wh(400.0, 200.0);
+        let layout = DockLayout::new();
+
+        let (path, side, _) = layout.target(viewport, 210.0, 90.0).unwrap();
+        assert_eq!((path, side), (Vec::<usize>::new(), DockSide::Center));
+        assert!(layout.target(viewport, 20.0, 20.0).is_none());
+    }
+}
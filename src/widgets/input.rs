@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use clipboard::{ClipboardContext,ClipboardProvider};
 use super::*;
 
@@ -10,9 +10,159 @@ pub enum InputState {
     Idle(f32, f32),
 }
 
+impl InputState {
+    pub fn scroll(&self) -> (f32, f32) {
+        match *self {
+            InputState::Selecting(_, _, _, sx, sy) |
+            InputState::Selected(_, _, _, sx, sy) |
+            InputState::Hovered(sx, sy) |
+            InputState::Idle(sx, sy) => (sx, sy),
+        }
+    }
+
+    pub fn set_scroll(&mut self, x: f32, y: f32) {
+        match *self {
+            InputState::Selecting(_, _, _, ref mut sx, ref mut sy) |
+            InputState::Selected(_, _, _, ref mut sx, ref mut sy) |
+            InputState::Hovered(ref mut sx, ref mut sy) |
+            InputState::Idle(ref mut sx, ref mut sy) => {
+                *sx = x;
+                *sy = y;
+            },
+        }
+    }
+}
+
+// Maximum number of undo steps kept by an input field
+const UNDO_LIMIT: usize = 100;
+// Maximum time between the clicks of a double or triple click, in milliseconds
+const MULTI_CLICK_TIME: u64 = 500;
+
+// The text and selection of an input field before or after an edit
+#[derive(Clone)]
+struct InputSnapshot {
+    text: String,
+    from: usize,
+    to: usize,
+}
+
+// Undo and redo stacks of an input field.
+#[derive(Clone)]
+pub struct InputHistory {
+    undo: Vec<InputSnapshot>,
+    redo: Vec<InputSnapshot>,
+    // Caret position after the last typed character. Characters typed at this position
+    //  extend the last undo step, so a typed run is undone at once.
+    typing: Option<usize>,
+}
+
+impl InputHistory {
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            typing: None,
+        }
+    }
+
+    // Record an edit, with the text and selection from before the edit.
+    fn record(&mut self, text: String, (from, to): (usize, usize), typed: Option<usize>) {
+        let continued = typed.is_some() && from == to && self.typing == Some(from);
+
+        if !continued {
+            self.undo.push(InputSnapshot { text, from, to });
+            if self.undo.len() > UNDO_LIMIT {
+                self.undo.remove(0);
+            }
+        }
+
+        self.redo.clear();
+        self.typing = typed;
+    }
+
+    // Restore the text before the last edit. Returns the selection to restore.
+    fn undo(&mut self, buffer: &mut String, (from, to): (usize, usize)) -> Option<(usize, usize)> {
+        self.typing = None;
+        self.undo.pop().map(|snapshot| {
+            let text = ::std::mem::replace(buffer, snapshot.text);
+            self.redo.push(InputSnapshot { text, from, to });
+            (snapshot.from, snapshot.to)
+        })
+    }
+
+    // Restore the text before the last undo. Returns the selection to restore.
+    fn redo(&mut self, buffer: &mut String, (from, to): (usize, usize)) -> Option<(usize, usize)> {
+        self.typing = None;
+        self.redo.pop().map(|snapshot| {
+            let text = ::std::mem::replace(buffer, snapshot.text);
+            self.undo.push(InputSnapshot { text, from, to });
+            (snapshot.from, snapshot.to)
+        })
+    }
+}
+
+// Counts successive clicks on the same caret position, for double and triple click selection.
+#[derive(Clone,Copy)]
+pub struct MultiClick {
+    count: usize,
+    position: usize,
+    time: Option<Instant>,
+}
+
+impl MultiClick {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            position: 0,
+            time: None,
+        }
+    }
+
+    // Register a click at `position`, returns 1, 2 or 3 for a single, double or triple click.
+    fn click(&mut self, position: usize) -> usize {
+        let now = Instant::now();
+        let repeated = self.position == position && self.time.map_or(false, |time| {
+            now.duration_since(time) < Duration::from_millis(MULTI_CLICK_TIME)
+        });
+
+        self.count = if repeated { self.count % 3 + 1 } else { 1 };
+        self.position = position;
+        self.time = Some(now);
+        self.count
+    }
+}
+
+// Text that is being composed by an input method. It is shown at the caret, but it is not part
+//  of the buffer until it is committed.
+#[derive(Clone)]
+pub struct Composition {
+    text: String,
+    cursor: Option<(usize, usize)>,
+}
+
+impl Composition {
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            cursor: None,
+        }
+    }
+
+    pub fn is_composing(&self) -> bool {
+        !self.text.is_empty()
+    }
+}
+
 pub struct Input<'a> {
     buffer: &'a mut String,
     password: bool,
+    multiline: bool,
+    max_length: Option<usize>,
+    filter: Option<Box<Fn(char) -> bool + 'a>>,
+    validator: Option<Box<Fn(&str) -> bool + 'a>>,
+    placeholder: Option<&'a str>,
+    // The visible height of a multiline field, PageUp and PageDown move the caret by it
+    page: Option<f32>,
     submit: bool,
 }
 
@@ -21,6 +171,12 @@ impl<'a> Input<'a> {
         Self {
             buffer: text,
             password: false,
+            multiline: false,
+            max_length: None,
+            filter: None,
+            validator: None,
+            placeholder: None,
+            page: None,
             submit: false,
         }
     }
@@ -29,26 +185,73 @@ impl<'a> Input<'a> {
         Self {
             buffer: text,
             password: true,
+            multiline: false,
+            max_length: None,
+            filter: None,
+            validator: None,
+            placeholder: None,
+            page: None,
             submit: false,
         }
     }
-}
 
-impl<'a> WidgetBase for Input<'a> {
-    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+    // An input field with word wrapped text, where enter inserts a new line.
+    // Use `TextArea` to get one that grows with it's text, to put it in a `Scroll`.
+    pub fn multiline(text: &'a mut String) -> Self {
+        Self {
+            buffer: text,
+            password: false,
+            multiline: true,
+            max_length: None,
+            filter: None,
+            validator: None,
+            placeholder: None,
+            page: None,
+            submit: false,
+        }
+    }
+
+    // Limit the number of characters in the field. Typed or pasted text that doesn't fit is dropped.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    // Only allow characters for which `filter` returns true to be typed or pasted.
+    pub fn with_filter<F: Fn(char) -> bool + 'a>(mut self, filter: F) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    // Check the full text with `validator`. Invalid text is shown with the `input_invalid` 
+    //  background, and enter will not submit it.
+    pub fn with_validator<F: Fn(&str) -> bool + 'a>(mut self, validator: F) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    // A hint that is shown when the field is empty
+    pub fn with_placeholder(mut self, placeholder: &'a str) -> Self {
+        self.placeholder = Some(placeholder);
+        self
+    }
+
+    // Create the components of the field with another layout, for widgets that are built on an input
+    pub fn create_with_layout(&mut self, id: dag::Id, world: &mut Ui, style: &Style, layout: Layout) {
         let text = Text {
             text: self.buffer.clone(),
             size: 16.0,
-            wrap: TextWrap::NoWrap,
+            wrap: if self.multiline { TextWrap::WordWrap } else { TextWrap::NoWrap },
             font: style.font.clone(),
             color: Color::black(),
             border: None,
             padding: Rect { left: 4.0, right: 4.0, top: 4.0, bottom: 4.0 },
         };
 
-        let layout = Layout::new().with_intrinsic_size_constraints(128.0, 32.0, 250.0);
-
         world.create_component(id, InputState::Idle(0.0, 0.0));
+        world.create_component(id, InputHistory::new());
+        world.create_component(id, MultiClick::new());
+        world.create_component(id, Composition::new());
         world.create_component(id, layout);
         world.create_component(id, text);
         world.create_component(id, Drawing::new());
@@ -60,7 +263,53 @@ impl<'a> WidgetBase for Input<'a> {
         });
     }
 
-    fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, _input: Option<Rect>) -> Option<Rect> {
+    fn valid(&self) -> bool {
+        self.validator.as_ref().map_or(true, |validator| validator(self.buffer))
+    }
+
+    // Apply the filter and the maximum length to `text`, that will replace `replaced` characters.
+    fn admit(&self, text: &str, replaced: usize) -> String {
+        let room = self.max_length.map_or(usize::max_value(), |max| {
+            (max + replaced).saturating_sub(self.buffer.chars().count())
+        });
+
+        text.chars()
+            .filter(|&c| self.filter.as_ref().map_or(true, |filter| filter(c)))
+            .take(room)
+            .collect()
+    }
+}
+
+impl<'a> WidgetBase for Input<'a> {
+    fn tabstop(&self) -> bool {
+        true
+    }
+
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let layout = if self.multiline {
+            Layout::new().with_intrinsic_size_constraints(256.0, 128.0, 250.0)
+        } else {
+            Layout::new().with_intrinsic_size_constraints(128.0, 32.0, 250.0)
+        };
+
+        self.create_with_layout(id, world, style, layout);
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, _input: Option<Rect>) -> Option<Rect> {
+        if self.multiline {
+            // the field may be taller than the `Scroll` it is in
+            let current = world.component::<Layout>(id).unwrap().borrow().current;
+            self.page = current.map(|current| style.input.content_rect(current)).and_then(|content| {
+                world.visible_rect().and_then(|visible| visible.intersect(&content))
+            }).map(|visible| visible.height());
+        }
+
+        if self.validator.is_some() {
+            let patch = if self.valid() { &style.input } else { &style.input_invalid };
+            let mut background = world.component::<WidgetBackground>(id).unwrap();
+            *background.borrow_mut() = WidgetBackground::new(Background::Patch(patch.clone(), 1.0));
+        }
+
         if !world.focus.map(|f| f.0 == id.0).unwrap_or(false) {
             let mut state = world.component(id).unwrap();
             let mut state = state.borrow_mut();
@@ -71,19 +320,41 @@ impl<'a> WidgetBase for Input<'a> {
                 InputState::Hovered(x, y) => InputState::Hovered(x, y),
                 InputState::Idle(x, y) => InputState::Idle(x, y),
             };
+
+            // composing ends when the field loses focus
+            let mut composition = world.component::<Composition>(id).unwrap();
+            let mut composition = composition.borrow_mut();
+            if composition.is_composing() {
+                *composition = Composition::new();
+                let mut text = world.component::<Text>(id).unwrap();
+                text.borrow_mut().text = text_display(self.buffer, self.password);
+            }
         }
         None
     }
     
     fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
-        let layout = world.component::<Layout>(id).unwrap();
-        let layout = layout.borrow();
+        let current = world.component::<Layout>(id).unwrap().borrow().current;
 
-        if layout.current.is_none() {
-            return;
+        if let Some(current) = current {
+            self.edit(id, world, style, context, current);
         }
+    }
+}
 
-        let current = layout.current.unwrap();
+impl<'a> Input<'a> {
+    // Handle the editing events for an input field that spans `current`.
+    // This is also used by widgets that embed an input field, those must create the same 
+    //  components as `Input` does.
+    pub fn edit(
+        &mut self, 
+        id: dag::Id, 
+        world: &mut Ui, 
+        style: &Style, 
+        context: &mut EventSystemContext, 
+        current: Rect
+    ) {
+        let bounds = world.component::<Layout>(id).unwrap().borrow().current.unwrap_or(current);
         let content = style.input.content_rect(current);
 
         let mut text = world.component::<Text>(id).unwrap();
@@ -92,6 +363,15 @@ impl<'a> WidgetBase for Input<'a> {
         let mut state = world.component::<InputState>(id).unwrap();
         let mut state = state.borrow_mut();
 
+        let mut history = world.component::<InputHistory>(id).unwrap();
+        let mut history = history.borrow_mut();
+
+        let mut clicks = world.component::<MultiClick>(id).unwrap();
+        let mut clicks = clicks.borrow_mut();
+
+        let mut composition = world.component::<Composition>(id).unwrap();
+        let mut composition = composition.borrow_mut();
+
         let mut clipper = world.component::<Clipper>(id).unwrap();
         clipper.borrow_mut().rect = content;
 
@@ -127,6 +407,20 @@ impl<'a> WidgetBase for Input<'a> {
             context.style = MouseStyle::Text;
         }
 
+        // the view of a multiline field only follows the caret when it moves
+        let caret_before = match *state {
+            InputState::Selecting(from, to, _, _, _) | InputState::Selected(from, to, _, _, _) => Some((from, to)),
+            _ => None,
+        };
+        let length_before = self.buffer.len();
+
+        // keep the text from before events that can edit it, for the undo history
+        let text_before = match context.event {
+            Event::Text(_) | Event::Press(_, _) | Event::Preedit(_, _) | Event::Commit(_) => Some(self.buffer.clone()),
+            _ => None,
+        };
+        let mut restored = false;
+
         // event related state update
         *state = match *state {
             InputState::Idle(sx, sy) => {
@@ -142,8 +436,9 @@ impl<'a> WidgetBase for Input<'a> {
             InputState::Hovered(sx, sy) => {
                 if let Event::Press(Key::LeftMouseButton, _) = context.event {
                     context.capture = Capture::CaptureFocus(MouseStyle::Text);
+                    let relative_cursor = (relative_cursor.0 + sx, relative_cursor.1 + sy);
                     let hit = text.hitdetect(relative_cursor, content);
-                    InputState::Selecting(hit, hit, Instant::now(), sx, sy)
+                    press(&mut clicks, &text.text, hit, sx, sy)
                 } else if context.cursor.inside(&current) {
                     InputState::Hovered(sx, sy)
                 } else {
@@ -166,81 +461,184 @@ impl<'a> WidgetBase for Input<'a> {
                 }
             },
             InputState::Selected(from, to, since, sx, sy) => match context.event {
-                Event::Press(Key::LeftMouseButton, _) => {
+                Event::Press(Key::LeftMouseButton, Modifiers{ shift, .. }) => {
                     if context.cursor.inside(&current) {
                         context.capture = Capture::CaptureFocus(MouseStyle::Text);
                         let relative_cursor = (relative_cursor.0 + sx, relative_cursor.1 + sy);
                         let hit = text.hitdetect(relative_cursor, content);
-                        InputState::Selecting(hit, hit, Instant::now(), sx, sy)
+                        if shift {
+                            // extend the selection
+                            InputState::Selecting(from, hit, Instant::now(), sx, sy)
+                        } else {
+                            press(&mut clicks, &text.text, hit, sx, sy)
+                        }
                     } else {
                         InputState::Idle(sx, sy)
                     }
                 },
 
-                Event::Text(c) => {
-                    match c {
-                        '\x08' => {
-                            let (from, to) = (from.min(to), from.max(to));
-
-                            if to > from {
-                                let pt = codepoint(self.buffer, from);
-                                let tail = self.buffer.split_off(pt);
-                                self.buffer.push_str(tail.split_at(codepoint(&tail, to-from)).1);
-                                text.text = text_display(self.buffer, self.password);
-
-                                InputState::Selected(from, from, Instant::now(), sx, sy)
-                            } else if from > 0 {
-                                let pt = codepoint(self.buffer, from-1);
-                                let tail = self.buffer.split_off(pt);
-                                self.buffer.push_str(tail.split_at(codepoint(&tail, 1)).1);
-                                text.text = text_display(self.buffer, self.password);
-
-                                InputState::Selected(from-1, from-1, Instant::now(), sx, sy)
-                            } else {
-                                InputState::Selected(from, to, Instant::now(), sx, sy)
-                            }                            
-                        },
-                        '\x7f' => {
-                            let (from, to) = (from.min(to), from.max(to));
-
-                            let pt = codepoint(self.buffer, from);
-                            let tail = self.buffer.split_off(pt);
-                            if to > from {
-                                self.buffer.push_str(tail.split_at(codepoint(&tail, to-from)).1);
-                            } else if tail.len() > 0 {
-                                self.buffer.push_str(tail.split_at(codepoint(&tail, 1)).1);
-                            }
-                            text.text = text_display(self.buffer, self.password);
+                Event::Text(c) => if c.is_control() || self.admit(&c.to_string(), from.max(to) - from.min(to)).is_empty() {
+                    InputState::Selected(from, to, since, sx, sy)
+                } else {
+                    let (from, to) = (from.min(to), from.max(to));
 
-                            InputState::Selected(from, from, Instant::now(), sx, sy)
-                        },
-                        c => if c.is_control() {
-                            InputState::Selected(from, to, since, sx, sy)
-                        } else {
-                            let (from, to) = (from.min(to), from.max(to));
-
-                            let pt = codepoint(self.buffer, from);
-                            let mut tail = self.buffer.split_off(pt);
-                            self.buffer.push(c);
-                            if to > from {
-                                let pt = codepoint(&tail, to-from);
-                                self.buffer.push_str(&tail.split_off(pt));
-                            } else {
-                                self.buffer.push_str(&tail);
-                            }
-                            text.text = text_display(self.buffer, self.password);
+                    let pt = codepoint(self.buffer, from);
+                    let mut tail = self.buffer.split_off(pt);
+                    self.buffer.push(c);
+                    if to > from {
+                        let pt = codepoint(&tail, to-from);
+                        self.buffer.push_str(&tail.split_off(pt));
+                    } else {
+                        self.buffer.push_str(&tail);
+                    }
+                    text.text = text_display(self.buffer, self.password);
 
-                            InputState::Selected(from+1, from+1, Instant::now(), sx, sy)
-                        }
+                    InputState::Selected(from+1, from+1, Instant::now(), sx, sy)
+                },
+
+                Event::Preedit(preedit, cursor) => {
+                    let preedit = world.composed(preedit);
+
+                    // the composition replaces the selection
+                    let (from, to) = (from.min(to), from.max(to));
+                    remove_range(self.buffer, from, to);
+
+                    composition.text = preedit.to_string();
+                    composition.cursor = cursor;
+
+                    let mut display = self.buffer.clone();
+                    let pt = codepoint(&display, from);
+                    display.insert_str(pt, preedit);
+                    text.text = text_display(&display, self.password);
+
+                    InputState::Selected(from, from, Instant::now(), sx, sy)
+                },
+
+                Event::Commit(committed) => {
+                    let committed = world.composed(committed);
+                    *composition = Composition::new();
+
+                    let (from, to) = (from.min(to), from.max(to));
+                    let committed = self.admit(committed, to - from);
+                    if !committed.is_empty() {
+                        remove_range(self.buffer, from, to);
+                        let pt = codepoint(self.buffer, from);
+                        self.buffer.insert_str(pt, &committed);
                     }
+                    text.text = text_display(self.buffer, self.password);
+
+                    let caret = if committed.is_empty() { to } else { from + committed.chars().count() };
+                    InputState::Selected(caret, caret, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::Backspace, Modifiers{ ctrl, .. }) => {
+                    let (from, to) = (from.min(to), from.max(to));
+                    let from = if to > from {
+                        from
+                    } else if ctrl {
+                        word_left(&text.text, from)
+                    } else {
+                        from.saturating_sub(1)
+                    };
+
+                    remove_range(self.buffer, from, to);
+                    text.text = text_display(self.buffer, self.password);
+
+                    InputState::Selected(from, from, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::Delete, Modifiers{ ctrl, .. }) => {
+                    let (from, to) = (from.min(to), from.max(to));
+                    let to = if to > from {
+                        to
+                    } else if ctrl {
+                        word_right(&text.text, to)
+                    } else {
+                        (to + 1).min(self.buffer.chars().count())
+                    };
+
+                    remove_range(self.buffer, from, to);
+                    text.text = text_display(self.buffer, self.password);
+
+                    InputState::Selected(from, from, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::A, Modifiers{ ctrl: true, .. }) => {
+                    let count = self.buffer.chars().count();
+                    InputState::Selected(0, count, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::Left, Modifiers{ ctrl: true, shift, .. }) => {
+                    let pos = word_left(&text.text, to);
+                    InputState::Selected(if shift { from } else { pos }, pos, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::Right, Modifiers{ ctrl: true, shift, .. }) => {
+                    let pos = word_right(&text.text, to);
+                    InputState::Selected(if shift { from } else { pos }, pos, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::Enter, _) if self.multiline && !self.admit("\n", from.max(to) - from.min(to)).is_empty() => {
+                    let (from, to) = (from.min(to), from.max(to));
+                    let (a, b) = (codepoint(self.buffer, from), codepoint(self.buffer, to));
+                    self.buffer.drain(a..b);
+                    self.buffer.insert(a, '\n');
+                    text.text = text_display(self.buffer, self.password);
+
+                    InputState::Selected(from+1, from+1, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::Up, Modifiers{ shift, .. }) if self.multiline => {
+                    let pos = move_lines(&text, to, -1.0, content);
+                    InputState::Selected(if shift { from } else { pos }, pos, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::Down, Modifiers{ shift, .. }) if self.multiline => {
+                    let pos = move_lines(&text, to, 1.0, content);
+                    InputState::Selected(if shift { from } else { pos }, pos, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::PageUp, Modifiers{ shift, .. }) if self.multiline => {
+                    let lines = (self.page.unwrap_or(content.height()) / text.line_height()).floor().max(1.0);
+                    let pos = move_lines(&text, to, -lines, content);
+                    InputState::Selected(if shift { from } else { pos }, pos, Instant::now(), sx, sy)
+                },
+
+                Event::Press(Key::PageDown, Modifiers{ shift, .. }) if self.multiline => {
+                    let lines = (self.page.unwrap_or(content.height()) / text.line_height()).floor().max(1.0);
+                    let pos = move_lines(&text, to, lines, content);
+                    InputState::Selected(if shift { from } else { pos }, pos, Instant::now(), sx, sy)
                 },
 
                 Event::Press(Key::Enter, Modifiers{ shift: false, .. }) => {
-                    self.submit = true;
+                    self.submit = self.valid();
 
                     InputState::Selected(from, to, since, sx, sy)
                 },
 
+                Event::Press(Key::Z, Modifiers{ ctrl: true, shift: false, .. }) => {
+                    restored = true;
+                    match history.undo(self.buffer, (from, to)) {
+                        Some((from, to)) => {
+                            text.text = text_display(self.buffer, self.password);
+                            InputState::Selected(from, to, Instant::now(), sx, sy)
+                        },
+                        None => InputState::Selected(from, to, since, sx, sy),
+                    }
+                },
+
+                Event::Press(Key::Z, Modifiers{ ctrl: true, shift: true, .. }) |
+                Event::Press(Key::Y, Modifiers{ ctrl: true, .. }) => {
+                    restored = true;
+                    match history.redo(self.buffer, (from, to)) {
+                        Some((from, to)) => {
+                            text.text = text_display(self.buffer, self.password);
+                            InputState::Selected(from, to, Instant::now(), sx, sy)
+                        },
+                        None => InputState::Selected(from, to, since, sx, sy),
+                    }
+                },
+
                 Event::Press(Key::C, Modifiers{ ctrl: true, .. }) => {
                     let (a, b) = (from.min(to), from.max(to));
                     let (a, b) = (codepoint(self.buffer, a), codepoint(self.buffer, b));
@@ -276,9 +674,9 @@ impl<'a> WidgetBase for Input<'a> {
                     let (from, to) = (from.min(to), from.max(to));
                     let paste_text = ClipboardContext::new().and_then(|mut cc| {
                         cc.get_contents()
-                    }).ok();
+                    }).ok().map(|paste_text| self.admit(&paste_text, to-from));
 
-                    if let Some(paste_text) = paste_text {
+                    if let Some(paste_text) = paste_text.filter(|paste_text| !paste_text.is_empty()) {
                         let pt = codepoint(self.buffer, from);
                         let mut tail = self.buffer.split_off(pt);
                         self.buffer.push_str(&paste_text);
@@ -290,12 +688,8 @@ impl<'a> WidgetBase for Input<'a> {
                         }
                         text.text = text_display(self.buffer, self.password);
 
-                        InputState::Selected(
-                            from+paste_text.len(), 
-                            from+paste_text.len(), 
-                            since,
-                            sx, sy
-                        )
+                        let count = paste_text.chars().count();
+                        InputState::Selected(from+count, from+count, since, sx, sy)
                     } else {
                         InputState::Selected(from, to, Instant::now(), sx, sy)
                     }
@@ -357,11 +751,57 @@ impl<'a> WidgetBase for Input<'a> {
             },
         };
 
+        // record the edit in the undo history
+        let caret_after = match *state {
+            InputState::Selecting(from, to, _, _, _) | InputState::Selected(from, to, _, _, _) => Some((from, to)),
+            _ => None,
+        };
+        if let Some(text_before) = text_before {
+            if !restored && text_before != *self.buffer {
+                let typed = match (&context.event, *state) {
+                    (&Event::Text(c), InputState::Selected(_, to, _, _, _)) if !c.is_control() => Some(to),
+                    _ => None,
+                };
+                history.record(text_before, caret_before.unwrap_or((0, 0)), typed);
+            } else if caret_before != caret_after {
+                // moving the caret ends a typed run
+                history.typing = None;
+            }
+        }
+
+        if self.multiline && context.cursor.inside(&current) {
+            if let Event::Scroll(_, dy) = context.event {
+                let (sx, sy) = state.scroll();
+                state.set_scroll(sx, sy - dy);
+            }
+        }
+
+        let moved = match *state {
+            InputState::Selecting(_, _, _, _, _) => true,
+            InputState::Selected(from, to, _, _, _) => 
+                caret_before.map_or(false, |before| before != (from, to)) || length_before != self.buffer.len(),
+            _ => false,
+        };
+
         // update scroll state for current text and caret position
         match state.deref_mut() {
+            &mut InputState::Selecting(_, pos, _, _, ref mut sy) |
+            &mut InputState::Selected(_, pos, _, _, ref mut sy) if self.multiline => {
+                if moved {
+                    let (caret, _) = text.measure_range(pos, pos, content);
+                    let top = caret.1 - text.ascent();
+                    let bottom = top + text.line_height();
+
+                    if bottom - *sy > content.height() {
+                        *sy = bottom - content.height();
+                    }
+                    if top - *sy < 0.0 {
+                        *sy = top;
+                    }
+                }
+            },
             &mut InputState::Selecting(_, pos, _, ref mut sx, ref mut sy) |
             &mut InputState::Selected(_, pos, _, ref mut sx, ref mut sy) => {
-                let content = style.input.content_rect(layout.current.unwrap());
                 let (caret, range) = text.measure_range(pos, text.text.chars().count(), content);
 
                 if *sx + content.width() > range.0 + 2.0 {
@@ -383,9 +823,29 @@ impl<'a> WidgetBase for Input<'a> {
             &mut _ => (),
         };
 
+        if self.multiline {
+            // multiline fields only scroll vertically, and not beyond the end of the text
+            let max = (text.measure(Some(content)).height() - content.height()).max(0.0);
+            let (_, sy) = state.scroll();
+            state.set_scroll(0.0, sy.min(max).max(0.0));
+        }
+
         // update rendering
         let scroll;
         drawing.primitives.clear();
+        drawing.primitives.push(Primitive::PushClip(content));
+
+        if let Some(placeholder) = self.placeholder {
+            if self.buffer.is_empty() && !composition.is_composing() {
+                let hint = Text {
+                    text: placeholder.to_string(),
+                    color: Color { r: 0.5, g: 0.5, b: 0.5, a: 1.0 },
+                    padding: Rect::zero(),
+                    ..text.clone()
+                };
+                drawing.primitives.push(Primitive::DrawText(hint, content));
+            }
+        }
         match state.deref() {
             &InputState::Idle(sx, sy) => {
                 scroll = (sx, sy);
@@ -398,7 +858,24 @@ impl<'a> WidgetBase for Input<'a> {
                 let range = text.measure_range(from.min(to), from.max(to), content);
                 scroll = (sx, sy);
 
-                if to != from {
+                if to != from && self.multiline {
+                    // a rectangle for every line in the selection
+                    let ((x0, y0), (x1, y1)) = range;
+                    let height = text.line_height();
+                    let lines = ((y1 - y0) / height).round() as usize;
+                    for i in 0..lines+1 {
+                        let top = content.top + y0 + i as f32 * height - text.ascent();
+                        drawing.primitives.push(Primitive::DrawRect(
+                            Rect {
+                                left: content.left + if i == 0 { x0 } else { 0.0 },
+                                right: content.left + if i == lines { x1 } else { content.width() },
+                                top: top,
+                                bottom: top + height,
+                            }.translate(-scroll.0, -scroll.1),
+                            Color{ r: 0.0, g: 0.0, b: 0.5, a: 0.5 }
+                        ));
+                    }
+                } else if to != from {
                     drawing.primitives.push(Primitive::DrawRect(
                         Rect {
                             left: content.left + (range.0).0,
@@ -410,31 +887,59 @@ impl<'a> WidgetBase for Input<'a> {
                     ));
                 } 
 
+                let caret = if composition.is_composing() {
+                    // underline the composition, the selected clause is underlined thicker
+                    let start = from.min(to);
+                    let length = composition.text.chars().count();
+                    let (cursor_from, cursor_to) = composition.cursor.unwrap_or((length, length));
+
+                    underline(&mut drawing.primitives, &text, start, start + length, 1.0, content, scroll);
+                    if cursor_to > cursor_from {
+                        underline(&mut drawing.primitives, &text, start + cursor_from, start + cursor_to, 2.0, content, scroll);
+                    }
+
+                    text.measure_range(start + cursor_to, start + cursor_to, content).0
+                } else if to > from {
+                    range.1
+                } else {
+                    range.0
+                };
+
+                let (top, bottom) = if self.multiline {
+                    let top = content.top + caret.1 - text.ascent();
+                    (top, top + text.line_height())
+                } else {
+                    (content.top, content.bottom)
+                };
+
+                let caret = Rect {
+                    left: content.left + caret.0,
+                    right: content.left + caret.0 + 1.0,
+                    top: top,
+                    bottom: bottom
+                }.translate(-scroll.0, -scroll.1);
+
                 if since.elapsed().subsec_nanos() < 500_000_000 {
-                    let caret = if to > from {
-                        range.1
-                    } else {
-                        range.0
-                    };
+                    drawing.primitives.push(Primitive::DrawRect(caret, Color{ r: 0.0, g: 0.0, b: 0.0, a: 1.0 }));
+                }
 
-                    drawing.primitives.push(Primitive::DrawRect(
-                        Rect {
-                            left: content.left + caret.0,
-                            right: content.left + caret.0 + 1.0,
-                            top: content.top,
-                            bottom: content.bottom
-                        }.translate(-scroll.0, -scroll.1),
-                        Color{ r: 0.0, g: 0.0, b: 0.0, a: 1.0 }
-                    ));
+                if context.focused {
+                    world.caret = Some(caret);
+
+                    // a multiline field may be in a `Scroll`, which keeps the caret in view
+                    if moved && self.multiline {
+                        world.reveal(caret);
+                    }
                 }
             },
         }
+        drawing.primitives.push(Primitive::PopClip);
 
         text.padding = Rect {
-            top: content.top - current.top,
-            bottom: current.bottom - content.bottom,
-            left: content.left - current.left - scroll.0,
-            right: current.right - content.right - scroll.1,
+            top: content.top - bounds.top - scroll.1,
+            bottom: bounds.bottom - content.bottom + scroll.1,
+            left: content.left - bounds.left - scroll.0,
+            right: bounds.right - content.right + scroll.0,
         };
     }
 }
@@ -457,4 +962,267 @@ fn text_display(buffer: &String, password: bool) -> String {
 
 fn codepoint(s: &String, char_index: usize) -> usize {
     s.char_indices().skip(char_index).next().map_or(s.len(), |(i,_)| i)
-}
\ No newline at end of file
+}
+
+fn remove_range(s: &mut String, from: usize, to: usize) {
+    let (a, b) = (codepoint(s, from), codepoint(s, to));
+    s.drain(a..b);
+}
+
+// Characters of the same class form a word, whitespace separates words
+fn word_class(c: char) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+// Caret position at the start of the word before `pos`
+fn word_left(s: &str, pos: usize) -> usize {
+    let chars: Vec<char> = s.chars().collect();
+    let mut i = pos.min(chars.len());
+
+    while i > 0 && word_class(chars[i-1]) == 0 {
+        i -= 1;
+    }
+    if i > 0 {
+        let class = word_class(chars[i-1]);
+        while i > 0 && word_class(chars[i-1]) == class {
+            i -= 1;
+        }
+    }
+    i
+}
+
+// Caret position at the start of the word after `pos`
+fn word_right(s: &str, pos: usize) -> usize {
+    let chars: Vec<char> = s.chars().collect();
+    let mut i = pos.min(chars.len());
+
+    if i < chars.len() {
+        let class = word_class(chars[i]);
+        while i < chars.len() && word_class(chars[i]) == class {
+            i += 1;
+        }
+    }
+    while i < chars.len() && word_class(chars[i]) == 0 {
+        i += 1;
+    }
+    i
+}
+
+// The range of the word that contains the caret position `pos`
+fn word_at(s: &str, pos: usize) -> (usize, usize) {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.is_empty() {
+        return (0, 0);
+    }
+
+    let pos = pos.min(chars.len() - 1);
+    let class = word_class(chars[pos]);
+
+    let mut from = pos;
+    while from > 0 && word_class(chars[from-1]) == class {
+        from -= 1;
+    }
+    let mut to = pos;
+    while to < chars.len() && word_class(chars[to]) == class {
+        to += 1;
+    }
+    (from, to)
+}
+
+// Start a selection for a mouse press at caret position `hit`. 
+// A double click selects a word and a triple click selects everything.
+fn press(clicks: &mut MultiClick, display: &str, hit: usize, sx: f32, sy: f32) -> InputState {
+    match clicks.click(hit) {
+        2 => {
+            let (from, to) = word_at(display, hit);
+            InputState::Selected(from, to, Instant::now(), sx, sy)
+        },
+        3 => InputState::Selected(0, display.chars().count(), Instant::now(), sx, sy),
+        _ => InputState::Selecting(hit, hit, Instant::now(), sx, sy),
+    }
+}
+
+// Draw a line below the text between the caret positions `from` and `to`
+fn underline(
+    primitives: &mut Vec<Primitive>, 
+    text: &Text, 
+    from: usize, 
+    to: usize, 
+    thickness: f32, 
+    content: Rect, 
+    scroll: (f32, f32)
+) {
+    let ((x0, y0), (x1, y1)) = text.measure_range(from, to, content);
+    let height = text.line_height();
+    let lines = ((y1 - y0) / height).round() as usize;
+    for i in 0..lines+1 {
+        let top = content.top + y0 + i as f32 * height + 1.0;
+        primitives.push(Primitive::DrawRect(
+            Rect {
+                left: content.left + if i == 0 { x0 } else { 0.0 },
+                right: content.left + if i == lines { x1 } else { content.width() },
+                top: top,
+                bottom: top + thickness,
+            }.translate(-scroll.0, -scroll.1),
+            Color{ r: 0.0, g: 0.0, b: 0.0, a: 1.0 }
+        ));
+    }
+}
+
+// Find the caret position `lines` lines below the caret at `pos`, or above when `lines` is negative.
+fn move_lines(text: &Text, pos: usize, lines: f32, content: Rect) -> usize {
+    let (caret, _) = text.measure_range(pos, pos, content);
+    text.hitdetect((caret.0, caret.1 + lines * text.line_height()), content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Type `c` at the caret position `pos`, like the text event does
+    fn type_char(history: &mut InputHistory, buffer: &mut String, pos: usize, c: char) {
+        let before = buffer.clone();
+        buffer.insert(codepoint(buffer, pos), c);
+        history.record(before, (pos, pos), Some(pos + 1));
+    }
+
+    #[test]
+    fn typed_run_is_undone_at_once() {
+        let mut history = InputHistory::new();
+        let mut buffer = String::new();
+
+        type_char(&mut history, &mut buffer, 0, 'a');
+        type_char(&mut history, &mut buffer, 1, 'b');
+        type_char(&mut history, &mut buffer, 2, 'c');
+        assert_eq!(buffer, "abc");
+
+        assert_eq!(history.undo(&mut buffer, (3, 3)), Some((0, 0)));
+        assert_eq!(buffer, "");
+        assert_eq!(history.undo(&mut buffer, (0, 0)), None);
+
+        assert_eq!(history.redo(&mut buffer, (0, 0)), Some((3, 3)));
+        assert_eq!(buffer, "abc");
+    }
+
+    #[test]
+    fn typing_elsewhere_or_other_edits_start_a_new_step() {
+        let mut history = InputHistory::new();
+        let mut buffer = String::new();
+
+        type_char(&mut history, &mut buffer, 0, 'a');
+        type_char(&mut history, &mut buffer, 1, 'b');
+        // the caret moved back before typing
+        type_char(&mut history, &mut buffer, 1, 'x');
+        assert_eq!(buffer, "axb");
+
+        // a deletion is never part of a typed run
+        history.record(buffer.clone(), (3, 3), None);
+        buffer.pop();
+        type_char(&mut history, &mut buffer, 2, 'y');
+        assert_eq!(buffer, "axy");
+
+        assert_eq!(history.undo(&mut buffer, (3, 3)), Some((2, 2)));
+        assert_eq!(buffer, "ax");
+        assert_eq!(history.undo(&mut buffer, (2, 2)), Some((3, 3)));
+        assert_eq!(buffer, "axb");
+        assert_eq!(history.undo(&mut buffer, (3, 3)), Some((1, 1)));
+        assert_eq!(buffer, "ab");
+        assert_eq!(history.undo(&mut buffer, (1, 1)), Some((0, 0)));
+        assert_eq!(buffer, "");
+    }
+
+    #[test]
+    fn new_edit_drops_redo() {
+        let mut history = InputHistory::new();
+        let mut buffer = String::new();
+
+        type_char(&mut history, &mut buffer, 0, 'a');
+        history.undo(&mut buffer, (1, 1));
+        assert_eq!(buffer, "");
+
+        type_char(&mut history, &mut buffer, 0, 'b');
+        assert_eq!(history.redo(&mut buffer, (1, 1)), None);
+        assert_eq!(buffer, "b");
+    }
+
+    #[test]
+    fn oldest_steps_are_dropped_at_the_limit() {
+        let mut history = InputHistory::new();
+        let mut buffer = String::new();
+
+        for i in 0..UNDO_LIMIT + 10 {
+            let before = ::std::mem::replace(&mut buffer, i.to_string());
+            history.record(before, (0, 0), None);
+        }
+
+        let mut steps = 0;
+        while history.undo(&mut buffer, (0, 0)).is_some() {
+            steps += 1;
+        }
+        assert_eq!(steps, UNDO_LIMIT);
+        // the text before the oldest step that was kept
+        assert_eq!(buffer, "9");
+    }
+
+    #[test]
+    fn word_left_skips_whitespace_then_one_word() {
+        assert_eq!(word_left("foo bar", 7), 4);
+        assert_eq!(word_left("foo bar", 4), 0);
+        assert_eq!(word_left("foo   bar", 6), 0);
+        assert_eq!(word_left("foo   ", 6), 0);
+        assert_eq!(word_left("snake_case", 10), 0);
+        // punctuation is a word of it's own
+        assert_eq!(word_left("foo.bar", 4), 3);
+        assert_eq!(word_left("foo.bar", 3), 0);
+        assert_eq!(word_left("a == b", 4), 2);
+        // start and end of the text
+        assert_eq!(word_left("foo", 0), 0);
+        assert_eq!(word_left("", 0), 0);
+        assert_eq!(word_left("foo bar", 100), 4);
+    }
+
+    #[test]
+    fn word_right_skips_one_word_then_whitespace() {
+        assert_eq!(word_right("foo bar", 0), 4);
+        assert_eq!(word_right("foo bar", 4), 7);
+        assert_eq!(word_right("foo   bar", 1), 6);
+        assert_eq!(word_right("   foo", 0), 3);
+        assert_eq!(word_right("snake_case x", 0), 11);
+        // punctuation is a word of it's own
+        assert_eq!(word_right("foo.bar", 0), 3);
+        assert_eq!(word_right("foo.bar", 3), 4);
+        assert_eq!(word_right("a, b", 1), 3);
+        // start and end of the text
+        assert_eq!(word_right("foo", 3), 3);
+        assert_eq!(word_right("", 0), 0);
+        assert_eq!(word_right("foo", 100), 3);
+    }
+
+    #[test]
+    fn word_at_selects_the_run_of_the_same_class() {
+        assert_eq!(word_at("foo bar", 1), (0, 3));
+        assert_eq!(word_at("foo bar", 0), (0, 3));
+        assert_eq!(word_at("foo bar", 4), (4, 7));
+        assert_eq!(word_at("foo.bar", 3), (3, 4));
+        assert_eq!(word_at("a   b", 2), (1, 4));
+        // the end of the text selects the last word
+        assert_eq!(word_at("foo bar", 7), (4, 7));
+        assert_eq!(word_at("", 0), (0, 0));
+    }
+
+    #[test]
+    fn word_positions_count_characters_not_bytes() {
+        assert_eq!(word_right("héllo wörld", 0), 6);
+        assert_eq!(word_left("héllo wörld", 11), 6);
+        assert_eq!(word_at("héllo wörld", 8), (6, 11));
+        assert_eq!(word_at("héllo—wörld", 5), (5, 6));
+        assert_eq!(word_right("日本 語", 0), 3);
+        assert_eq!(word_left("日本 語", 4), 3);
+    }
+}
@@ -1,13 +1,55 @@
 use super::*;
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
 use std::mem::replace;
 use std::time::Instant;
 
+// Space left and right of the label of a menu item
+const MENU_PADDING: f32 = 8.0;
+const MENU_ITEM_HEIGHT: f32 = 24.0;
+// Height of a separator in a menu, or it's width in a menu bar
+const MENU_SEPARATOR_SIZE: f32 = 8.0;
+
 pub enum MenuItem<'a, T: 'a> {
     Separator,
     StringItem(T, &'a str, &'a[MenuItem<'a, T>]),
     IconItem(T, Image, &'a str, &'a[MenuItem<'a, T>]),
 }
 
+impl<'a, T: 'a> MenuItem<'a, T> {
+    fn value(&self) -> Option<&T> {
+        match self {
+            &MenuItem::Separator => None,
+            &MenuItem::StringItem(ref value, _, _) |
+            &MenuItem::IconItem(ref value, _, _, _) => Some(value),
+        }
+    }
+
+    fn label(&self) -> &'a str {
+        match self {
+            &MenuItem::Separator => "",
+            &MenuItem::StringItem(_, label, _) |
+            &MenuItem::IconItem(_, _, label, _) => label,
+        }
+    }
+
+    fn items(&self) -> &'a[MenuItem<'a, T>] {
+        match self {
+            &MenuItem::Separator => &[],
+            &MenuItem::StringItem(_, _, sub) |
+            &MenuItem::IconItem(_, _, _, sub) => sub,
+        }
+    }
+
+    fn width(&self, measure: &Fn(&str) -> f32) -> f32 {
+        match self {
+            &MenuItem::Separator => 0.0,
+            &MenuItem::StringItem(_, label, _) => measure(label) + MENU_PADDING * 2.0,
+            &MenuItem::IconItem(_, ref icon, label, _) => icon.size.width() + measure(label) + MENU_PADDING * 3.0,
+        }
+    }
+}
+
 pub struct Menu<'a, T: 'a> {
     menu: &'a[MenuItem<'a, T>],
     result: Option<&'a T>,
@@ -35,7 +77,9 @@ impl<'a, T:'a> WidgetBase for Menu<'a, T> {
         world.create_component(id, MenuState::Idle);
     }
 
-    fn event(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, context: &mut EventSystemContext) {
+    fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        let measure = |label: &str| menu_text(style, label).measure(None).width();
+
         let mut state = world.component::<MenuState>(id).unwrap();
         let mut state = state.borrow_mut();
 
@@ -54,6 +98,7 @@ impl<'a, T:'a> WidgetBase for Menu<'a, T> {
                     0, (x, y), 
                     BorrowMutMenuPath{ x: &mut path }, 
                     time,
+                    &measure,
                     |rect, _, _, _| {
                         context.cursor.inside(&rect)
                     }
@@ -81,12 +126,13 @@ impl<'a, T:'a> WidgetBase for Menu<'a, T> {
                     0, (x, y), 
                     BorrowMenuPath{ x: &path }, 
                     time,
+                    &measure,
                     |rect, item, hovered, _| {
                         if context.cursor.inside(&rect) {
                             cursor_outside = false;
                             match &context.event {
                                 &Event::Press(Key::LeftMouseButton, _) => {
-                                    self.result = Some(item);
+                                    self.result = item.value();
                                 },
                                 &_ => (),
                             }
@@ -125,6 +171,7 @@ impl<'a, T:'a> WidgetBase for Menu<'a, T> {
                         0, (x, y), 
                         BorrowMenuPath{ x: &path }, 
                         time,
+                        &measure,
                         |rect, _, hovered, _| {
                             if hovered {
                                 drawing.primitives.push(Primitive::DrawRect(rect, Color{ r: 0.0, g: 0.0, b: 1.0, a: 1.0 }));
@@ -148,6 +195,462 @@ impl<'a, T:'a> Widget for Menu<'a, T> {
     }
 }
 
+// A bar with the top level items of a menu next to each other. Clicking an item opens it's
+//  submenu below it, alt focuses the bar so the menus can be used with the arrow keys.
+pub struct MenuBar<'a, T: 'a> {
+    menu: &'a[MenuItem<'a, T>],
+    result: Option<&'a T>,
+}
+
+#[derive(Clone,Copy,PartialEq)]
+enum MenuBarMode {
+    Idle,
+    // The bar has the keyboard focus, but no submenu is open
+    Focused,
+    Open,
+}
+
+#[derive(Clone)]
+struct MenuBarState {
+    mode: MenuBarMode,
+    // The highlighted top level item
+    current: usize,
+    // The highlighted items of the open submenus
+    path: MenuPath,
+    time: Instant,
+    hovered: Option<usize>,
+    // Items are chosen in event(), so they are reported in the next frame
+    chosen: Option<(usize, MenuPath)>,
+}
+
+impl<'a, T:'a> MenuBar<'a, T> {
+    pub fn new(menu: &'a[MenuItem<'a, T>]) -> Self {
+        Self {
+            menu, result: None
+        }
+    }
+
+    // The rects of the top level items, separators get an empty rect
+    fn item_rects(&self, style: &Style, current: Rect) -> Vec<Rect> {
+        let content = current.after_padding(style.menu_bar.margin());
+        let measure = |label: &str| menu_text(style, label).measure(None).width();
+
+        let mut x = content.left;
+        self.menu.iter().map(|item| {
+            let width = match item {
+                &MenuItem::Separator => MENU_SEPARATOR_SIZE,
+                item => item.width(&measure),
+            };
+            let rect = match item {
+                &MenuItem::Separator => Rect { left: x, top: content.top, right: x, bottom: content.bottom },
+                _ => Rect { left: x, top: content.top, right: x + width, bottom: content.bottom },
+            };
+            x += width;
+            rect
+        }).collect()
+    }
+
+    // Where the submenu of a top level item opens
+    fn origin(&self, style: &Style, current: Rect, item: Rect) -> (f32, f32) {
+        let margin = style.popup.margin();
+        (item.left + margin.left, current.bottom + margin.top)
+    }
+
+    // Open the submenu of a top level item, with the first item highlighted when it's opened
+    //  with the keyboard
+    fn open(&self, state: &mut MenuBarState, index: usize, keyboard: bool) {
+        state.mode = MenuBarMode::Open;
+        state.current = index;
+        state.path = MenuPath::new();
+        state.time = Instant::now();
+        if keyboard {
+            if let Some(first) = step(self.menu[index].items(), None, true) {
+                state.path.push(first as i8);
+            }
+        }
+    }
+}
+
+// The next item that isn't a separator, wrapping around at the ends
+fn step<'a, T: 'a>(menu: &[MenuItem<'a, T>], from: Option<usize>, forward: bool) -> Option<usize> {
+    let count = menu.len();
+    (1..count + 1)
+        .map(|i| match from {
+            Some(from) if forward => (from + i) % count,
+            Some(from) => (from + count - i) % count,
+            None if forward => i - 1,
+            None => count - i,
+        })
+        .find(|&i| match menu[i] {
+            MenuItem::Separator => false,
+            _ => true,
+        })
+}
+
+// The submenu that contains the last item of `path`
+fn submenu_at<'a, T: 'a>(menu: &'a[MenuItem<'a, T>], path: &[i8]) -> &'a[MenuItem<'a, T>] {
+    let parents = path.len().max(1) - 1;
+    path[..parents].iter().fold(menu, |menu, &index| {
+        if index >= 0 && (index as usize) < menu.len() {
+            menu[index as usize].items()
+        } else {
+            &[][..]
+        }
+    })
+}
+
+fn menu_text(style: &Style, label: &str) -> Text {
+    Text {
+        text: label.to_string(),
+        font: style.font.clone(),
+        size: 16.0,
+        wrap: TextWrap::NoWrap,
+        color: Color::black(),
+        border: None,
+        padding: Rect::zero(),
+    }
+}
+
+impl<'a, T:'a> WidgetBase for MenuBar<'a, T> {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let margin = style.menu_bar.margin();
+        let measure = |label: &str| menu_text(style, label).measure(None).width();
+
+        let width = self.menu.iter().fold(0.0, |acc, item| match item {
+            &MenuItem::Separator => acc + MENU_SEPARATOR_SIZE,
+            item => acc + item.width(&measure),
+        });
+        let height = menu_text(style, "").measure(None).height().max(MENU_ITEM_HEIGHT);
+
+        world.create_component(id, Layout::new()
+            .with_margins(margin)
+            .with_constraints(|layout| vec![
+                layout.width |GE(STRONG)| (width + margin.left + margin.right) as f64,
+                layout.height |EQ(STRONG)| (height + margin.top + margin.bottom) as f64,
+            ]));
+        world.create_component(id, WidgetBackground::new(Background::Patch(style.menu_bar.clone(), 1.0)));
+        world.create_component(id, Drawing::new());
+        world.create_component(id, Overlay::new());
+        world.create_component(id, MenuBarState {
+            mode: MenuBarMode::Idle,
+            current: 0,
+            path: MenuPath::new(),
+            time: Instant::now(),
+            hovered: None,
+            chosen: None,
+        });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, _input: Option<Rect>) -> Option<Rect> {
+        let mut state = world.component::<MenuBarState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        self.result = state.chosen.take().and_then(|(index, path)| {
+            let menu = self.menu.get(index).map_or(&[][..], |item| item.items());
+            path.last()
+                .filter(|&&last| last >= 0)
+                .and_then(|&last| submenu_at(menu, &path).get(last as usize))
+                .and_then(|item| item.value())
+        });
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = layout.borrow().current;
+
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+        drawing.primitives.clear();
+
+        let mut overlay = world.component::<Overlay>(id).unwrap();
+        let mut overlay = overlay.borrow_mut();
+        overlay.primitives.clear();
+
+        let current = match current {
+            Some(current) => current,
+            None => return None,
+        };
+
+        let highlight = Color { r: 0.2, g: 0.4, b: 0.8, a: 1.0 };
+        let rects = self.item_rects(style, current);
+
+        for (index, (item, &rect)) in self.menu.iter().zip(rects.iter()).enumerate() {
+            if let &MenuItem::Separator = item {
+                continue;
+            }
+
+            let highlighted = match state.mode {
+                MenuBarMode::Idle => state.hovered == Some(index),
+                _ => state.current == index,
+            };
+
+            let mut text = menu_text(style, item.label());
+            if highlighted {
+                drawing.primitives.push(Primitive::DrawRect(rect, highlight));
+                text.color = Color::white();
+            }
+
+            let mut content = rect.after_padding(Rect { left: MENU_PADDING, top: 0.0, right: MENU_PADDING, bottom: 0.0 });
+            if let &MenuItem::IconItem(_, ref icon, _, _) = item {
+                let size = icon.size;
+                let top = ((content.top + content.bottom - size.height()) * 0.5).floor();
+                drawing.primitives.push(Primitive::DrawImage(icon.clone(), Rect::from_xywh(content.left, top, size.width(), size.height()), Color::white()));
+                content.left += size.width() + MENU_PADDING;
+            }
+
+            let height = text.measure(None).height();
+            let top = ((content.top + content.bottom - height) * 0.5).floor();
+            drawing.primitives.push(Primitive::DrawText(text, Rect { top, bottom: top + height, ..content }));
+        }
+
+        if state.mode == MenuBarMode::Open && state.current < self.menu.len() {
+            let measure = |label: &str| menu_text(style, label).measure(None).width();
+            let origin = self.origin(style, current, rects[state.current]);
+
+            // the items of every submenu share their left edge, one popup is drawn per submenu
+            let mut items: Vec<(Rect, &MenuItem<T>, bool, bool)> = vec![];
+            for_each_item(
+                self.menu[state.current].items(),
+                0, origin,
+                BorrowMenuPath{ x: &state.path },
+                state.time,
+                &measure,
+                |rect, item, hovered, recursive| {
+                    items.push((rect, item, hovered, recursive));
+                    hovered
+                }
+            );
+
+            let mut start = 0;
+            while start < items.len() {
+                let left = items[start].0.left;
+                let end = items[start..].iter().position(|&(rect, _, _, _)| rect.left != left).map_or(items.len(), |n| start + n);
+                let panel = items[start..end].iter().fold(items[start].0, |acc, &(rect, _, _, _)| acc.union(rect));
+
+                overlay.primitives.push(Primitive::Draw9(style.popup.clone(), panel.after_margin(style.popup.margin()), Color::white()));
+
+                for &(rect, item, hovered, recursive) in items[start..end].iter() {
+                    let mut text = menu_text(style, item.label());
+                    if hovered {
+                        overlay.primitives.push(Primitive::DrawRect(rect, highlight));
+                        text.color = Color::white();
+                    }
+
+                    let mut content = rect.after_padding(Rect { left: MENU_PADDING, top: 0.0, right: MENU_PADDING, bottom: 0.0 });
+                    if let &MenuItem::IconItem(_, ref icon, _, _) = item {
+                        let size = icon.size;
+                        let top = ((content.top + content.bottom - size.height()) * 0.5).floor();
+                        overlay.primitives.push(Primitive::DrawImage(icon.clone(), Rect::from_xywh(content.left, top, size.width(), size.height()), Color::white()));
+                        content.left += size.width() + MENU_PADDING;
+                    }
+
+                    let height = text.measure(None).height();
+                    let top = ((content.top + content.bottom - height) * 0.5).floor();
+                    let content = Rect { top, bottom: top + height, ..content };
+
+                    // items with a submenu show an arrow
+                    if recursive {
+                        let mut arrow = menu_text(style, ">");
+                        arrow.color = text.color;
+                        let width = arrow.measure(None).width();
+                        overlay.primitives.push(Primitive::DrawText(arrow, Rect { left: content.right - width, ..content }));
+                    }
+                    overlay.primitives.push(Primitive::DrawText(text, content));
+                }
+
+                start = end;
+            }
+        }
+
+        None
+    }
+
+    fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        let mut state = world.component::<MenuBarState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = match layout.borrow().current {
+            Some(current) => current,
+            None => return,
+        };
+
+        let measure = |label: &str| menu_text(style, label).measure(None).width();
+        let rects = self.item_rects(style, current);
+
+        // separators can't be hovered
+        let hovered = rects.iter().position(|rect| context.cursor.inside(rect))
+            .filter(|&index| self.menu[index].value().is_some());
+        state.hovered = hovered;
+
+        match state.mode {
+            MenuBarMode::Idle | MenuBarMode::Focused => {
+                let focused = state.mode == MenuBarMode::Focused;
+                let from = state.current;
+                match context.event {
+                    Event::Press(Key::LeftMouseButton, _) => {
+                        match hovered {
+                            Some(index) => self.open(&mut state, index, false),
+                            None => state.mode = MenuBarMode::Idle,
+                        }
+                    },
+                    Event::Press(Key::Alt, _) if !focused => {
+                        if let Some(first) = step(self.menu, None, true) {
+                            state.mode = MenuBarMode::Focused;
+                            state.current = first;
+                        }
+                    },
+                    Event::Press(Key::Alt, _) |
+                    Event::Press(Key::Escape, _) |
+                    Event::Press(Key::Tab, _) if focused => {
+                        state.mode = MenuBarMode::Idle;
+                    },
+                    Event::Press(Key::Left, _) if focused => {
+                        state.current = step(self.menu, Some(from), false).unwrap_or(from);
+                    },
+                    Event::Press(Key::Right, _) if focused => {
+                        state.current = step(self.menu, Some(from), true).unwrap_or(from);
+                    },
+                    Event::Press(Key::Down, _) |
+                    Event::Press(Key::Enter, _) |
+                    Event::Press(Key::Space, _) if focused => {
+                        self.open(&mut state, from, true);
+                    },
+                    _ => (),
+                }
+            },
+
+            MenuBarMode::Open => {
+                // hovering another top level item switches to it's submenu
+                if let Some(index) = hovered {
+                    if index != state.current {
+                        self.open(&mut state, index, false);
+                    }
+                }
+
+                // the submenus escape the clipping of the parents, so use the full viewport for
+                //  hit tests
+                let cursor = context.cursor.expand(&world.viewport);
+                let origin = self.origin(style, current, rects[state.current]);
+                let menu = self.menu[state.current].items();
+
+                if let Event::Cursor(_, _) = context.event {
+                    let mut path = state.path.clone();
+                    state.time = for_each_item(
+                        menu, 0, origin,
+                        BorrowMutMenuPath{ x: &mut path },
+                        state.time,
+                        &measure,
+                        |rect, _, _, _| cursor.inside(&rect)
+                    );
+                    state.path = path;
+                }
+
+                // the highlighted item of the innermost submenu
+                let highlighted = state.path.last()
+                    .filter(|&&index| index >= 0)
+                    .and_then(|&index| submenu_at(menu, &state.path).get(index as usize));
+                let parents = state.path.len().max(1) - 1;
+                let level = submenu_at(menu, &state.path);
+                let index = state.path.last().and_then(|&index| if index >= 0 { Some(index as usize) } else { None });
+
+                match context.event {
+                    Event::Press(Key::LeftMouseButton, _) => {
+                        let mut clicked = None;
+                        for_each_item(
+                            menu, 0, origin,
+                            BorrowMenuPath{ x: &state.path },
+                            state.time,
+                            &measure,
+                            |rect, _, hovered, recursive| {
+                                if cursor.inside(&rect) {
+                                    clicked = Some(recursive);
+                                }
+                                hovered
+                            }
+                        );
+
+                        match clicked {
+                            // items with a submenu only open it
+                            Some(true) => (),
+                            Some(false) => {
+                                state.chosen = Some((state.current, state.path.clone()));
+                                state.mode = MenuBarMode::Idle;
+                            },
+                            // clicking the open item again or outside of the menus closes them
+                            None => if hovered.is_none() || hovered == Some(state.current) {
+                                state.mode = MenuBarMode::Idle;
+                            },
+                        }
+                    },
+                    Event::Press(Key::Up, _) => {
+                        if let Some(next) = step(level, index, false) {
+                            state.path.truncate(parents);
+                            state.path.push(next as i8);
+                        }
+                    },
+                    Event::Press(Key::Down, _) => {
+                        if let Some(next) = step(level, index, true) {
+                            state.path.truncate(parents);
+                            state.path.push(next as i8);
+                        }
+                    },
+                    Event::Press(Key::Right, _) => {
+                        match highlighted.and_then(|item| step(item.items(), None, true)) {
+                            Some(first) => state.path.push(first as i8),
+                            None => {
+                                let from = state.current;
+                                let next = step(self.menu, Some(from), true).unwrap_or(from);
+                                self.open(&mut state, next, true);
+                            },
+                        }
+                    },
+                    Event::Press(Key::Left, _) => {
+                        if state.path.len() > 1 {
+                            state.path.pop();
+                        } else {
+                            let from = state.current;
+                            let next = step(self.menu, Some(from), false).unwrap_or(from);
+                            self.open(&mut state, next, true);
+                        }
+                    },
+                    Event::Press(Key::Enter, _) |
+                    Event::Press(Key::Space, _) => {
+                        match highlighted {
+                            Some(item) => match step(item.items(), None, true) {
+                                Some(first) => state.path.push(first as i8),
+                                None => {
+                                    state.chosen = Some((state.current, state.path.clone()));
+                                    state.mode = MenuBarMode::Idle;
+                                },
+                            },
+                            None => (),
+                        }
+                    },
+                    Event::Press(Key::Escape, _) => {
+                        state.mode = MenuBarMode::Focused;
+                    },
+                    Event::Press(Key::Alt, _) |
+                    Event::Press(Key::Tab, _) => {
+                        state.mode = MenuBarMode::Idle;
+                    },
+                    _ => (),
+                }
+            },
+        }
+
+        if state.mode != MenuBarMode::Idle {
+            context.capture = Capture::CaptureFocus(MouseStyle::Arrow);
+        }
+    }
+}
+
+impl<'a, T:'a> Widget for MenuBar<'a, T> {
+    type Result = Option<&'a T>;
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result {
+        self.result
+    }
+}
+
 trait MenuPathWrapper {
     fn truncate(&mut self, length: usize);
     fn push(&mut self, item: i8);
@@ -176,7 +679,7 @@ impl<'a> MenuPathWrapper for BorrowMutMenuPath<'a> {
 
 fn for_each_item<
     'a, 
-    F: FnMut(Rect, &'a T, bool, bool) -> bool, 
+    F: FnMut(Rect, &'a MenuItem<'a, T>, bool, bool) -> bool, 
     W: MenuPathWrapper,
     T
 > (
@@ -185,10 +688,11 @@ fn for_each_item<
     position: (f32, f32),
     mut path: W, 
     mut time: Instant,
+    measure: &Fn(&str) -> f32,
     mut f: F
 ) -> Instant {
     // find widest item
-    let width = slice.iter().fold(0.0, |_acc, _item| 32.0);
+    let width = slice.iter().fold(32.0, |acc: f32, item| acc.max(item.width(measure)));
 
     // layout items
     let x = position.0;
@@ -197,17 +701,17 @@ fn for_each_item<
     let mut i = 0;
 
     for item in slice {
-        let (item, height, recursive) = match item {
+        let (height, recursive) = match item {
             &MenuItem::Separator => {
                 i += 1;
-                y += 8.0;
+                y += MENU_SEPARATOR_SIZE;
                 continue;
             },
-            &MenuItem::StringItem(ref item, _, sub) => { 
-                (item, 24.0, sub.len() > 0)
+            &MenuItem::StringItem(_, _, sub) => { 
+                (MENU_ITEM_HEIGHT, sub.len() > 0)
             },
-            &MenuItem::IconItem(ref item, ref icon, _, sub) => {
-                (item, icon.size.height().max(24.0), sub.len() > 0)
+            &MenuItem::IconItem(_, ref icon, _, sub) => {
+                (icon.size.height().max(MENU_ITEM_HEIGHT), sub.len() > 0)
             },
         };
 
@@ -252,7 +756,7 @@ fn for_each_item<
                     &MenuItem::IconItem(_, _, _, sub) => {
                         if sub.len() > 0 {
                             time = for_each_item(
-                                sub, depth+1, (x+width, selected_y.unwrap()), path, time, f
+                                sub, depth+1, (x+width, selected_y.unwrap()), path, time, measure, f
                             );
                         }
                     },
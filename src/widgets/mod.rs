@@ -10,6 +10,19 @@ pub mod input;
 pub mod window;
 pub mod menu;
 pub mod collection;
+pub mod tooltip;
+pub mod combo;
+pub mod slider;
+pub mod progress;
+pub mod number;
+pub mod text_area;
+pub mod tabs;
+pub mod tree;
+pub mod table;
+pub mod virtual_list;
+pub mod splitter;
+pub mod dock;
+pub mod dialog;
 
 pub use self::frame::*;
 pub use self::label::*;
@@ -21,6 +34,19 @@ pub use self::input::*;
 pub use self::window::*;
 pub use self::menu::*;
 pub use self::collection::*;
+pub use self::tooltip::*;
+pub use self::combo::*;
+pub use self::slider::*;
+pub use self::progress::*;
+pub use self::number::*;
+pub use self::text_area::*;
+pub use self::tabs::*;
+pub use self::tree::*;
+pub use self::table::*;
+pub use self::virtual_list::*;
+pub use self::splitter::*;
+pub use self::dock::*;
+pub use self::dialog::*;
 
 pub trait WidgetBase {
     fn tabstop(&self) -> bool { 
@@ -60,6 +86,9 @@ pub struct Style {
     pub button_pressed: Patch,
 
     pub input: Patch,
+    pub input_invalid: Patch,
+    pub spin_up: Image,
+    pub spin_down: Image,
 
     pub checkbox_checked_normal: Image,
     pub checkbox_checked_hover: Image,
@@ -78,7 +107,33 @@ pub struct Style {
     pub scroll_horizontal: (Patch, Patch),
     pub scroll_vertical: (Patch, Patch),
 
+    pub slider_horizontal: (Patch, Patch),
+    pub slider_vertical: (Patch, Patch),
+
+    pub progress: (Patch, Patch),
+
     pub window: Patch,
+    pub window_title: Patch,
+    pub window_close: Image,
+    pub window_collapse: Image,
+    pub window_expand: Image,
+
+    pub tooltip: Patch,
+
+    pub popup: Patch,
+    pub menu_bar: Patch,
+    pub combo_arrow: Image,
+
+    pub tab_normal: Patch,
+    pub tab_hover: Patch,
+    pub tab_selected: Patch,
+    pub tab_close: Image,
+
+    pub tree_expanded: Image,
+    pub tree_collapsed: Image,
+
+    pub sort_ascending: Image,
+    pub sort_descending: Image,
 }
 
 impl Style {
@@ -116,16 +171,63 @@ impl Style {
                 ui.get_image(load_from_static_memory!("../../img/radio_checked_pressed.png")),
             input: 
                 ui.get_patch(load_from_static_memory!("../../img/input.png")),
+            input_invalid: 
+                ui.get_patch(load_from_static_memory!("../../img/input_invalid.png")),
             font: 
                 ui.get_font(load_from_static_memory!("../../img/default_font.ttf")),
             window: 
                 ui.get_patch(load_from_static_memory!("../../img/window.png")),
+            window_title: 
+                ui.get_patch(load_from_static_memory!("../../img/window_title.png")),
+            window_close: 
+                ui.get_image(load_from_static_memory!("../../img/window_close.png")),
+            window_collapse: 
+                ui.get_image(load_from_static_memory!("../../img/window_collapse.png")),
+            window_expand: 
+                ui.get_image(load_from_static_memory!("../../img/window_expand.png")),
             scroll_horizontal: 
                 (ui.get_patch(load_from_static_memory!("../../img/scroll_bg.png")),
                  ui.get_patch(load_from_static_memory!("../../img/scroll_bar.png"))),
             scroll_vertical: 
                 (ui.get_patch(load_from_static_memory!("../../img/scroll_bg.png")),
                  ui.get_patch(load_from_static_memory!("../../img/scroll_bar.png"))),
+            slider_horizontal: 
+                (ui.get_patch(load_from_static_memory!("../../img/slider_track.png")),
+                 ui.get_patch(load_from_static_memory!("../../img/slider_thumb.png"))),
+            slider_vertical: 
+                (ui.get_patch(load_from_static_memory!("../../img/slider_track.png")),
+                 ui.get_patch(load_from_static_memory!("../../img/slider_thumb.png"))),
+            progress: 
+                (ui.get_patch(load_from_static_memory!("../../img/progress_bg.png")),
+                 ui.get_patch(load_from_static_memory!("../../img/progress_fill.png"))),
+            tooltip: 
+                ui.get_patch(load_from_static_memory!("../../img/tooltip.png")),
+            popup: 
+                ui.get_patch(load_from_static_memory!("../../img/popup.png")),
+            menu_bar: 
+                ui.get_patch(load_from_static_memory!("../../img/menu_bar.png")),
+            combo_arrow: 
+                ui.get_image(load_from_static_memory!("../../img/combo_arrow.png")),
+            spin_up: 
+                ui.get_image(load_from_static_memory!("../../img/spin_up.png")),
+            spin_down: 
+                ui.get_image(load_from_static_memory!("../../img/spin_down.png")),
+            tab_normal: 
+                ui.get_patch(load_from_static_memory!("../../img/tab_normal.png")),
+            tab_hover: 
+                ui.get_patch(load_from_static_memory!("../../img/tab_hover.png")),
+            tab_selected: 
+                ui.get_patch(load_from_static_memory!("../../img/tab_selected.png")),
+            tab_close: 
+                ui.get_image(load_from_static_memory!("../../img/tab_close.png")),
+            tree_expanded: 
+                ui.get_image(load_from_static_memory!("../../img/tree_expanded.png")),
+            tree_collapsed: 
+                ui.get_image(load_from_static_memory!("../../img/tree_collapsed.png")),
+            sort_ascending: 
+                ui.get_image(load_from_static_memory!("../../img/sort_ascending.png")),
+            sort_descending: 
+                ui.get_image(load_from_static_memory!("../../img/sort_descending.png")),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,314 @@
+use super::*;
+use std::time::{Duration, Instant};
+
+// Width of the increment/decrement buttons
+const SPIN_WIDTH: f32 = 16.0;
+// Delay before a held spin button starts repeating, and the interval between repeats
+const SPIN_DELAY: u64 = 400;
+const SPIN_REPEAT: u64 = 50;
+// Distance the mouse must move before a press turns into a drag
+const DRAG_THRESHOLD: f32 = 3.0;
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum NumberDrag {
+    Idle,
+    // The mouse was pressed on the field, but it was not moved far enough to start dragging
+    Pressed(f32),
+    // Dragging, with the unclamped value that is being dragged
+    Dragging(f64),
+}
+
+#[derive(Clone)]
+pub struct NumberInputState {
+    text: String,
+    editing: bool,
+    drag: NumberDrag,
+    // Held spin button, with the direction and the time of the next repeat
+    spin: Option<(f64, Instant)>,
+    // The new value is decided in event(), it is reported by the widget in the next frame.
+    pending: Option<f64>,
+}
+
+pub struct NumberInput<T: Numeric> {
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+    step: Option<T>,
+    precision: usize,
+    drag_speed: Option<f64>,
+    result: Option<T>,
+}
+
+impl<T: Numeric> NumberInput<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            min: None,
+            max: None,
+            step: None,
+            precision: 2,
+            drag_speed: None,
+            result: None,
+        }
+    }
+
+    pub fn with_range(mut self, min: T, max: T) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    // The amount the value changes with the spin buttons and the up/down keys. Defaults to 1.
+    pub fn with_step(mut self, step: T) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    // Number of decimals shown for floating point values. Defaults to 2.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    // Allow changing the value by dragging the mouse horizontally over the field.
+    // `speed` is the change in value per pixel. A click without dragging edits the text.
+    pub fn with_drag(mut self, speed: f64) -> Self {
+        self.drag_speed = Some(speed);
+        self
+    }
+
+    fn clamp(&self, value: f64) -> f64 {
+        let value = self.min.map_or(value, |min| value.max(min.to_f64()));
+        self.max.map_or(value, |max| value.min(max.to_f64()))
+    }
+
+    fn step(&self) -> f64 {
+        self.step.map_or(1.0, |step| step.to_f64())
+    }
+
+    fn format(&self, value: f64) -> String {
+        T::from_f64(value).format(self.precision)
+    }
+
+    // Split the widget in the text field and the up and down spin buttons
+    fn parts(&self, current: Rect) -> (Rect, Rect, Rect) {
+        let middle = ((current.top + current.bottom) * 0.5).floor();
+        let field = Rect { right: current.right - SPIN_WIDTH, ..current };
+        let up = Rect { left: field.right, bottom: middle, ..current };
+        let down = Rect { left: field.right, top: middle, ..current };
+        (field, up, down)
+    }
+}
+
+impl<T: Numeric> WidgetBase for NumberInput<T> {
+    fn tabstop(&self) -> bool {
+        true
+    }
+
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let mut text = self.format(self.value.to_f64());
+        Input::new(&mut text).create(id, world, style);
+
+        world.create_component(id, NumberInputState {
+            text,
+            editing: false,
+            drag: NumberDrag::Idle,
+            spin: None,
+            pending: None,
+        });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let mut field = {
+            let mut state = world.component::<NumberInputState>(id).unwrap();
+            let mut state = state.borrow_mut();
+
+            let mut value = state.pending.take();
+
+            let focused = world.focus.map_or(false, |f| f.0 == id.0);
+            if state.editing && !focused {
+                // commit the text when the field loses focus, invalid text is reverted
+                state.editing = false;
+                value = value.or(T::parse(&state.text).map(|v| v.to_f64()));
+            }
+
+            self.result = value.map(|v| T::from_f64(self.clamp(v)));
+            if let Some(value) = self.result {
+                self.value = value;
+            }
+
+            if !state.editing {
+                state.text = self.format(self.value.to_f64());
+            }
+
+            let mut text = world.component::<Text>(id).unwrap();
+            if text.borrow().text != state.text {
+                text.borrow_mut().text = state.text.clone();
+            }
+
+            state.text.clone()
+        };
+
+        Input::new(&mut field).update(id, world, style, input)
+    }
+
+    fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        let current = match world.component::<Layout>(id).unwrap().borrow().current {
+            Some(current) => current,
+            None => return,
+        };
+
+        let (field, up, down) = self.parts(current);
+
+        let mut state = world.component::<NumberInputState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        let value = state.pending.unwrap_or(self.value.to_f64());
+
+        // spin buttons
+        if let Event::Press(Key::LeftMouseButton, _) = context.event {
+            let direction = if context.cursor.inside(&up) {
+                Some(1.0)
+            } else if context.cursor.inside(&down) {
+                Some(-1.0)
+            } else {
+                None
+            };
+
+            if let Some(direction) = direction {
+                state.pending = Some(self.clamp(value + direction * self.step()));
+                state.spin = Some((direction, Instant::now() + Duration::from_millis(SPIN_DELAY)));
+                state.editing = false;
+            }
+        }
+
+        if let Some((direction, next)) = state.spin {
+            context.capture = Capture::CaptureFocus(MouseStyle::ArrowClicking);
+
+            if let Event::Release(Key::LeftMouseButton, _) = context.event {
+                state.spin = None;
+            } else if Instant::now() >= next {
+                state.pending = Some(self.clamp(value + direction * self.step()));
+                state.spin = Some((direction, next + Duration::from_millis(SPIN_REPEAT)));
+            }
+        } else if let Some(speed) = self.drag_speed {
+            // drag to change the value
+            state.drag = match state.drag {
+                NumberDrag::Idle => match context.event {
+                    Event::Press(Key::LeftMouseButton, _) if !context.focused && context.cursor.inside(&field) => {
+                        context.capture = Capture::CaptureMouse(MouseStyle::ResizeWe);
+                        NumberDrag::Pressed(0.0)
+                    },
+                    _ => NumberDrag::Idle,
+                },
+                NumberDrag::Pressed(distance) => {
+                    context.capture = Capture::CaptureMouse(MouseStyle::ResizeWe);
+                    match context.event {
+                        Event::Motion(dx, _) if (distance + dx).abs() > DRAG_THRESHOLD => {
+                            // lock the cursor, so dragging doesn't stop at the edge of the screen
+                            context.mode = MouseMode::Locked(context.cursor.x, context.cursor.y);
+                            NumberDrag::Dragging(value + (distance + dx) as f64 * speed)
+                        },
+                        Event::Motion(dx, _) => {
+                            NumberDrag::Pressed(distance + dx)
+                        },
+                        Event::Release(Key::LeftMouseButton, _) => {
+                            // a click without dragging selects the text, it's edited once it changes
+                            let count = state.text.chars().count();
+                            let mut input_state = world.component::<InputState>(id).unwrap();
+                            *input_state.borrow_mut() = InputState::Selected(0, count, Instant::now(), 0.0, 0.0);
+                            context.capture = Capture::CaptureFocus(MouseStyle::Text);
+                            NumberDrag::Idle
+                        },
+                        _ => NumberDrag::Pressed(distance),
+                    }
+                },
+                NumberDrag::Dragging(dragged) => {
+                    context.capture = Capture::CaptureMouse(MouseStyle::ResizeWe);
+                    match context.event {
+                        Event::Motion(dx, _) => {
+                            let dragged = dragged + dx as f64 * speed;
+                            state.pending = Some(self.clamp(dragged));
+                            NumberDrag::Dragging(dragged)
+                        },
+                        Event::Release(Key::LeftMouseButton, _) => {
+                            context.mode = MouseMode::Normal;
+                            NumberDrag::Idle
+                        },
+                        _ => NumberDrag::Dragging(dragged),
+                    }
+                },
+            };
+        }
+
+        let busy = state.spin.is_some() || state.drag != NumberDrag::Idle;
+
+        if !busy {
+            let handled = match context.event {
+                Event::Press(Key::Up, _) if context.focused => {
+                    state.pending = Some(self.clamp(value + self.step()));
+                    true
+                },
+                Event::Press(Key::Down, _) if context.focused => {
+                    state.pending = Some(self.clamp(value - self.step()));
+                    true
+                },
+                Event::Press(Key::Enter, _) if context.focused => {
+                    state.pending = T::parse(&state.text).map(|v| v.to_f64()).or(state.pending);
+                    true
+                },
+                Event::Press(Key::Escape, _) if context.focused => true,
+                _ => false,
+            };
+
+            // the field is redrawn for every event, keys that were handled here don't reach it
+            let mut text = state.text.clone();
+            if handled {
+                state.editing = false;
+                let event = ::std::mem::replace(&mut context.event, Event::Idle);
+                Input::new(&mut text).edit(id, world, style, context, field);
+                context.event = event;
+            } else {
+                Input::new(&mut text).edit(id, world, style, context, field);
+                if text != state.text {
+                    state.text = text;
+                    state.editing = true;
+                }
+            }
+        }
+
+        // draw the spin buttons on top of the input field
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+
+        if busy {
+            // the text is not edited while spinning or dragging
+            drawing.primitives.clear();
+        }
+
+        for &(rect, ref image, direction) in [(up, &style.spin_up, 1.0), (down, &style.spin_down, -1.0)].iter() {
+            let patch = match state.spin {
+                Some((d, _)) if d == direction => &style.button_pressed,
+                _ => if context.cursor.inside(&rect) { &style.button_hover } else { &style.button_normal },
+            };
+            let size = image.size;
+            let left = ((rect.left + rect.right - size.width()) * 0.5).floor();
+            let top = ((rect.top + rect.bottom - size.height()) * 0.5).floor();
+
+            drawing.primitives.push(Primitive::Draw9(patch.clone(), rect, Color::white()));
+            drawing.primitives.push(Primitive::DrawImage(
+                (*image).clone(),
+                Rect::from_xywh(left, top, size.width(), size.height()),
+                Color::white()
+            ));
+        }
+    }
+}
+
+impl<T: Numeric> Widget for NumberInput<T> {
+    type Result = Option<T>;
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result {
+        self.result
+    }
+}
@@ -0,0 +1,272 @@
+use super::*;
+
+use std::borrow::Cow;
+use std::f32::consts::PI;
+use std::time::Instant;
+
+// Duration of a single cycle of the indeterminate animation, in seconds
+const INDETERMINATE_PERIOD: f32 = 1.5;
+// Width of the column strips that make up a radial fill
+const RADIAL_STRIP: f32 = 2.0;
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum ProgressDirection {
+    // Fills from left to right
+    Horizontal,
+    // Fills from bottom to top
+    Vertical,
+    // Fills clockwise, starting at the top
+    Radial,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum ProgressFill {
+    // The fill patch is drawn at full size and clipped to the progress
+    Clip,
+    // The fill patch is stretched to the progress
+    Stretch,
+}
+
+#[derive(Clone,Copy)]
+struct ProgressState {
+    since: Instant,
+}
+
+pub struct ProgressBar<'a> {
+    progress: Option<f32>,
+    direction: ProgressDirection,
+    fill: ProgressFill,
+    label: Option<Cow<'a, str>>,
+}
+
+impl<'a> ProgressBar<'a> {
+    // A progress bar showing a fraction between 0.0 and 1.0
+    pub fn new(progress: f32) -> Self {
+        Self {
+            progress: Some(progress.max(0.0).min(1.0)),
+            direction: ProgressDirection::Horizontal,
+            fill: ProgressFill::Clip,
+            label: None,
+        }
+    }
+
+    // A progress bar for work of unknown length, it shows an animation.
+    pub fn indeterminate() -> Self {
+        Self {
+            progress: None,
+            direction: ProgressDirection::Horizontal,
+            fill: ProgressFill::Clip,
+            label: None,
+        }
+    }
+
+    pub fn with_direction(mut self, direction: ProgressDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    // Radial progress bars are always clipped.
+    pub fn with_fill(mut self, fill: ProgressFill) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    pub fn with_label(mut self, label: &'a str) -> Self {
+        self.label = Some(Cow::from(label));
+        self
+    }
+
+    pub fn with_label_owned(mut self, label: String) -> Self {
+        self.label = Some(Cow::from(label));
+        self
+    }
+
+    // Show the progress as a percentage
+    pub fn with_percentage(self) -> Self {
+        match self.progress {
+            Some(progress) => {
+                let label = format!("{}%", (progress * 100.0).round());
+                self.with_label_owned(label)
+            },
+            None => self,
+        }
+    }
+
+    fn draw_linear(&self, primitives: &mut Vec<Primitive>, fill: &Patch, rect: Rect, from: f32, to: f32) {
+        let part = match self.direction {
+            ProgressDirection::Vertical => Rect {
+                top: (rect.bottom - rect.height() * to).floor(),
+                bottom: (rect.bottom - rect.height() * from).floor(),
+                ..rect
+            },
+            _ => Rect {
+                left: (rect.left + rect.width() * from).floor(),
+                right: (rect.left + rect.width() * to).floor(),
+                ..rect
+            },
+        };
+
+        if part.width() <= 0.0 || part.height() <= 0.0 {
+            return;
+        }
+
+        match self.fill {
+            ProgressFill::Clip => {
+                primitives.push(Primitive::PushClip(part));
+                primitives.push(Primitive::Draw9(fill.clone(), rect, Color::white()));
+                primitives.push(Primitive::PopClip);
+            },
+            ProgressFill::Stretch => {
+                primitives.push(Primitive::Draw9(fill.clone(), part, Color::white()));
+            },
+        }
+    }
+
+    // The filled sector is drawn as thin column strips. Within a column, the part of the sector
+    //  between 12 o'clock and the angle `progress * 2 * PI` is always a single range.
+    fn draw_radial(&self, primitives: &mut Vec<Primitive>, fill: &Patch, rect: Rect, progress: f32) {
+        if progress <= 0.0 {
+            return;
+        }
+
+        if progress >= 1.0 {
+            primitives.push(Primitive::Draw9(fill.clone(), rect, Color::white()));
+            return;
+        }
+
+        let angle = progress * 2.0 * PI;
+        let (sin, cos) = angle.sin_cos();
+        let cx = (rect.left + rect.right) * 0.5;
+        let cy = (rect.top + rect.bottom) * 0.5;
+
+        let mut x = rect.left;
+        while x < rect.right {
+            let strip = Rect {
+                left: x,
+                right: (x + RADIAL_STRIP).min(rect.right),
+                ..rect
+            };
+            let dx = (strip.left + strip.right) * 0.5 - cx;
+
+            // where the edge of the sector crosses this column
+            let edge = |dx: f32| (cy - dx * cos / sin).max(rect.top).min(rect.bottom);
+
+            let range = if dx >= 0.0 {
+                // the right half is swept top to bottom during the first half of the turn
+                if angle >= PI {
+                    Some((rect.top, rect.bottom))
+                } else {
+                    Some((rect.top, edge(dx)))
+                }
+            } else {
+                // the left half is swept bottom to top during the second half of the turn
+                if angle <= PI {
+                    None
+                } else {
+                    Some((edge(dx), rect.bottom))
+                }
+            };
+
+            if let Some((top, bottom)) = range {
+                if bottom > top {
+                    primitives.push(Primitive::PushClip(Rect { top, bottom, ..strip }));
+                    primitives.push(Primitive::Draw9(fill.clone(), rect, Color::white()));
+                    primitives.push(Primitive::PopClip);
+                }
+            }
+
+            x += RADIAL_STRIP;
+        }
+    }
+}
+
+impl<'a> WidgetBase for ProgressBar<'a> {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let size = style.progress.0.image.size;
+
+        let layout = match self.direction {
+            ProgressDirection::Horizontal =>
+                Layout::new().with_intrinsic_size_constraints(128.0, size.height(), 250.0),
+            ProgressDirection::Vertical =>
+                Layout::new().with_intrinsic_size_constraints(size.width(), 128.0, 250.0),
+            ProgressDirection::Radial =>
+                Layout::new().with_intrinsic_size_constraints(64.0, 64.0, 250.0),
+        };
+
+        world.create_component(id, layout);
+        world.create_component(id, Drawing::new());
+        world.create_component(id, ProgressState { since: Instant::now() });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, _input: Option<Rect>) -> Option<Rect> {
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = layout.borrow().current;
+
+        let state = world.component::<ProgressState>(id).unwrap();
+        let since = state.borrow().since;
+
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+        drawing.primitives.clear();
+
+        let current = match current {
+            Some(current) => current,
+            None => return None,
+        };
+
+        let &(ref background, ref fill) = &style.progress;
+
+        drawing.primitives.push(Primitive::Draw9(background.clone(), current, Color::white()));
+
+        match self.progress {
+            Some(progress) => {
+                if self.direction == ProgressDirection::Radial {
+                    self.draw_radial(&mut drawing.primitives, fill, current, progress);
+                } else {
+                    self.draw_linear(&mut drawing.primitives, fill, current, 0.0, progress);
+                }
+            },
+            None => {
+                let elapsed = since.elapsed();
+                let time = elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 * 1e-9;
+                let phase = (time % INDETERMINATE_PERIOD) / INDETERMINATE_PERIOD;
+
+                if self.direction == ProgressDirection::Radial {
+                    self.draw_radial(&mut drawing.primitives, fill, current, phase);
+                } else {
+                    // a segment that bounces between both ends
+                    let position = 1.0 - (phase * 2.0 - 1.0).abs();
+                    let from = position * 0.75;
+                    self.draw_linear(&mut drawing.primitives, fill, current, from, from + 0.25);
+                }
+            },
+        }
+
+        if let Some(ref label) = self.label {
+            let text = Text {
+                text: label.to_string(),
+                size: 16.0,
+                wrap: TextWrap::NoWrap,
+                font: style.font.clone(),
+                color: Color::black(),
+                border: None,
+                padding: Rect::zero(),
+            };
+
+            let measured = text.measure(None);
+            let left = ((current.left + current.right - measured.width()) * 0.5).floor();
+            let top = ((current.top + current.bottom - measured.height()) * 0.5).floor();
+            let rect = Rect::from_xywh(left, top, measured.width(), measured.height());
+
+            drawing.primitives.push(Primitive::DrawText(text, rect));
+        }
+
+        None
+    }
+}
+
+impl<'a> Widget for ProgressBar<'a> {
+    type Result = ();
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result { }
+}
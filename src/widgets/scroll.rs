@@ -110,6 +110,41 @@ impl WidgetBase for Scroll {
         state.scroll.0 = (state.scroll.0).max(self.content.left).min(self.content.right);
         state.scroll.1 = (state.scroll.1).max(self.content.top).min(self.content.bottom);
 
+        // a child asked to be scrolled into view, like the caret of a text area
+        if let Some(reveal) = world.reveal {
+            let content = Rect::from_xywh(
+                current.left - state.scroll.0,
+                current.top - state.scroll.1,
+                self.content.right + current.width(),
+                self.content.bottom + current.height()
+            );
+
+            if reveal.intersect(&content).is_some() {
+                let mut scroll = state.scroll;
+                if reveal.right > padded.right {
+                    scroll.0 += reveal.right - padded.right;
+                }
+                if reveal.left < padded.left {
+                    scroll.0 -= padded.left - reveal.left;
+                }
+                if reveal.bottom > padded.bottom {
+                    scroll.1 += reveal.bottom - padded.bottom;
+                }
+                if reveal.top < padded.top {
+                    scroll.1 -= padded.top - reveal.top;
+                }
+                scroll.0 = scroll.0.max(self.content.left).min(self.content.right);
+                scroll.1 = scroll.1.max(self.content.top).min(self.content.bottom);
+
+                if scroll != state.scroll {
+                    state.scroll = scroll;
+                    world.layout_solver.suggest_value(state.scroll_vars.0, scroll.0 as f64).ok();
+                    world.layout_solver.suggest_value(state.scroll_vars.1, scroll.1 as f64).ok();
+                    world.component::<ScrollState>(id).unwrap().borrow_mut().scroll = scroll;
+                }
+            }
+        }
+
         let vertical_rect = {
             let mut bar = Rect { 
                 left: padded.right, 
@@ -0,0 +1,318 @@
+use super::*;
+
+#[derive(Clone,Copy,PartialEq)]
+pub enum SliderState {
+    Idle,
+    Hovered,
+    // Dragging the thumb, with the grab offset relative to the center of the thumb
+    Drag(f32),
+}
+
+// The new value is decided in event(), it is reported by the widget in the next frame.
+#[derive(Clone,Copy)]
+struct SliderValue(Option<f64>);
+
+pub struct Slider<T: Numeric> {
+    value: T,
+    min: T,
+    max: T,
+    step: Option<T>,
+    ticks: usize,
+    vertical: bool,
+    result: Option<T>,
+}
+
+impl<T: Numeric> Slider<T> {
+    pub fn horizontal(value: T, min: T, max: T) -> Self {
+        Self {
+            value,
+            min,
+            max,
+            step: None,
+            ticks: 0,
+            vertical: false,
+            result: None,
+        }
+    }
+
+    pub fn vertical(value: T, min: T, max: T) -> Self {
+        Self {
+            vertical: true,
+            ..Self::horizontal(value, min, max)
+        }
+    }
+
+    // Restrict the value to multiples of `step`, counted from `min`.
+    // The step is also used for keyboard and scroll wheel adjustment.
+    pub fn with_step(mut self, step: T) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    // Draw `count` evenly spaced tick marks along the track, including both ends.
+    pub fn with_ticks(mut self, count: usize) -> Self {
+        self.ticks = count;
+        self
+    }
+
+    fn patches<'b>(&self, style: &'b Style) -> &'b (Patch, Patch) {
+        if self.vertical {
+            &style.slider_vertical
+        } else {
+            &style.slider_horizontal
+        }
+    }
+
+    // The range in which the center of the thumb can move
+    fn range(&self, style: &Style, current: Rect) -> (f32, f32) {
+        let thumb = self.patches(style).1.image.size;
+        if self.vertical {
+            (current.top + thumb.height() * 0.5, current.bottom - thumb.height() * 0.5)
+        } else {
+            (current.left + thumb.width() * 0.5, current.right - thumb.width() * 0.5)
+        }
+    }
+
+    // Relative position of a value between min and max
+    fn fraction(&self, value: f64) -> f32 {
+        let (min, max) = (self.min.to_f64(), self.max.to_f64());
+        if max > min {
+            ((value - min) / (max - min)).max(0.0).min(1.0) as f32
+        } else {
+            0.0
+        }
+    }
+
+    fn position(&self, style: &Style, current: Rect, value: f64) -> f32 {
+        let (begin, end) = self.range(style, current);
+        let fraction = self.fraction(value);
+        if self.vertical {
+            end - (end - begin) * fraction
+        } else {
+            begin + (end - begin) * fraction
+        }
+    }
+
+    fn value_at(&self, style: &Style, current: Rect, position: f32) -> f64 {
+        let (begin, end) = self.range(style, current);
+        let fraction = if end > begin {
+            ((position - begin) / (end - begin)).max(0.0).min(1.0) as f64
+        } else {
+            0.0
+        };
+        let fraction = if self.vertical { 1.0 - fraction } else { fraction };
+        let (min, max) = (self.min.to_f64(), self.max.to_f64());
+        self.snap(min + (max - min) * fraction)
+    }
+
+    fn snap(&self, value: f64) -> f64 {
+        let (min, max) = (self.min.to_f64(), self.max.to_f64());
+        let value = self.step.map_or(value, |step| {
+            let step = step.to_f64();
+            if step > 0.0 {
+                min + ((value - min) / step).round() * step
+            } else {
+                value
+            }
+        });
+        value.max(min).min(max)
+    }
+
+    // The amount the value changes by a single key press or scroll wheel notch
+    fn increment(&self) -> f64 {
+        self.step.map_or_else(|| {
+            let increment = (self.max.to_f64() - self.min.to_f64()) / 100.0;
+            // smaller increments would be rounded away by integer types
+            if T::is_integer() { increment.max(1.0) } else { increment }
+        }, |step| step.to_f64())
+    }
+
+    fn thumb(&self, style: &Style, current: Rect, value: f64) -> Rect {
+        let thumb = self.patches(style).1.image.size;
+        let position = self.position(style, current, value);
+        if self.vertical {
+            let left = ((current.left + current.right - thumb.width()) * 0.5).floor();
+            let top = (position - thumb.height() * 0.5).floor();
+            Rect::from_xywh(left, top, thumb.width(), thumb.height())
+        } else {
+            let left = (position - thumb.width() * 0.5).floor();
+            let top = ((current.top + current.bottom - thumb.height()) * 0.5).floor();
+            Rect::from_xywh(left, top, thumb.width(), thumb.height())
+        }
+    }
+}
+
+impl<T: Numeric> WidgetBase for Slider<T> {
+    fn tabstop(&self) -> bool {
+        true
+    }
+
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let thumb = self.patches(style).1.image.size;
+
+        let layout = if self.vertical {
+            Layout::new().with_intrinsic_size_constraints(thumb.width(), 128.0, 250.0)
+        } else {
+            Layout::new().with_intrinsic_size_constraints(128.0, thumb.height(), 250.0)
+        };
+
+        world.create_component(id, layout);
+        world.create_component(id, Drawing::new());
+        world.create_component(id, SliderState::Idle);
+        world.create_component(id, SliderValue(None));
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, _input: Option<Rect>) -> Option<Rect> {
+        let mut pending = world.component::<SliderValue>(id).unwrap();
+        self.result = pending.borrow_mut().0.take().map(T::from_f64);
+        if let Some(value) = self.result {
+            self.value = value;
+        }
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = layout.borrow().current;
+
+        let state = world.component::<SliderState>(id).unwrap();
+        let state = *state.borrow();
+
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+        drawing.primitives.clear();
+
+        if let Some(current) = current {
+            let &(ref track, ref thumb) = self.patches(style);
+            let (begin, end) = self.range(style, current);
+            let thickness = track.image.size.height().min(track.image.size.width());
+
+            let track_rect = if self.vertical {
+                let left = ((current.left + current.right - thickness) * 0.5).floor();
+                Rect { left, top: begin, right: left + thickness, bottom: end }
+            } else {
+                let top = ((current.top + current.bottom - thickness) * 0.5).floor();
+                Rect { left: begin, top, right: end, bottom: top + thickness }
+            };
+
+            drawing.primitives.push(Primitive::Draw9(track.clone(), track_rect, Color::white()));
+
+            if self.ticks > 1 {
+                let color = Color::black().with_alpha(0.5);
+                for i in 0..self.ticks {
+                    let position = (begin + (end - begin) * i as f32 / (self.ticks - 1) as f32).floor();
+                    let tick = if self.vertical {
+                        Rect { left: current.left, top: position, right: current.left + 4.0, bottom: position + 1.0 }
+                    } else {
+                        Rect { left: position, top: current.bottom - 4.0, right: position + 1.0, bottom: current.bottom }
+                    };
+                    drawing.primitives.push(Primitive::DrawRect(tick, color));
+                }
+            }
+
+            let color = match state {
+                SliderState::Idle => Color::white(),
+                _ => Color { r: 0.9, g: 0.9, b: 1.0, a: 1.0 },
+            };
+            let thumb_rect = self.thumb(style, current, self.value.to_f64());
+            drawing.primitives.push(Primitive::Draw9(thumb.clone(), thumb_rect, color));
+        }
+
+        None
+    }
+
+    fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = match layout.borrow().current {
+            Some(current) => current,
+            None => return,
+        };
+
+        let mut state = world.component::<SliderState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        let mut pending = world.component::<SliderValue>(id).unwrap();
+        let mut pending = pending.borrow_mut();
+
+        let value = pending.0.unwrap_or(self.value.to_f64());
+        let thumb = self.thumb(style, current, value);
+        let cursor = if self.vertical { context.cursor.y } else { context.cursor.x };
+        let center = if self.vertical {
+            (thumb.top + thumb.bottom) * 0.5
+        } else {
+            (thumb.left + thumb.right) * 0.5
+        };
+
+        *state = match *state {
+            SliderState::Idle | SliderState::Hovered => {
+                let hovered = context.cursor.inside(&current);
+
+                match context.event {
+                    Event::Press(Key::LeftMouseButton, _) if hovered => {
+                        context.capture = Capture::CaptureMouse(MouseStyle::ArrowClicking);
+                        if context.cursor.inside(&thumb) {
+                            // keep the thumb at the same position relative to the cursor
+                            SliderState::Drag(cursor - center)
+                        } else {
+                            // jump to the clicked position
+                            pending.0 = Some(self.value_at(style, current, cursor));
+                            SliderState::Drag(0.0)
+                        }
+                    },
+                    _ => if hovered {
+                        SliderState::Hovered
+                    } else {
+                        SliderState::Idle
+                    },
+                }
+            },
+            SliderState::Drag(offset) => {
+                context.capture = Capture::CaptureMouse(MouseStyle::ArrowClicking);
+
+                match context.event {
+                    Event::Release(Key::LeftMouseButton, _) => {
+                        if context.cursor.inside(&current) {
+                            SliderState::Hovered
+                        } else {
+                            SliderState::Idle
+                        }
+                    },
+                    Event::Cursor(_, _) => {
+                        pending.0 = Some(self.value_at(style, current, cursor - offset));
+                        SliderState::Drag(offset)
+                    },
+                    _ => SliderState::Drag(offset),
+                }
+            },
+        };
+
+        // keyboard and scroll wheel adjustment
+        let increment = self.increment();
+        let adjust = match context.event {
+            Event::Scroll(dx, dy) if *state != SliderState::Idle => {
+                let delta = if self.vertical || dx == 0.0 { dy } else { dx };
+                if delta > 0.0 {
+                    Some(increment)
+                } else if delta < 0.0 {
+                    Some(-increment)
+                } else {
+                    None
+                }
+            },
+            Event::Press(Key::Right, _) | Event::Press(Key::Up, _) if context.focused => Some(increment),
+            Event::Press(Key::Left, _) | Event::Press(Key::Down, _) if context.focused => Some(-increment),
+            Event::Press(Key::Home, _) if context.focused => Some(self.min.to_f64() - value),
+            Event::Press(Key::End, _) if context.focused => Some(self.max.to_f64() - value),
+            _ => None,
+        };
+
+        if let Some(delta) = adjust {
+            pending.0 = Some(self.snap(value + delta));
+        }
+    }
+}
+
+impl<T: Numeric> Widget for Slider<T> {
+    type Result = Option<T>;
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result {
+        self.result
+    }
+}
@@ -0,0 +1,440 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use std::time::{Duration, Instant};
+use super::*;
+
+// Thickness of the dividers between the panes
+const DIVIDER_SIZE: f32 = 6.0;
+// Extra distance around a divider where it can still be grabbed
+const DIVIDER_GRAB: f32 = 2.0;
+// Time in milliseconds between two presses on a divider to count as a double click
+const MULTI_CLICK_TIME: u64 = 500;
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum SplitDirection {
+    // The panes are next to each other, with vertical dividers
+    Horizontal,
+    // The panes are above each other, with horizontal dividers
+    Vertical,
+}
+
+#[derive(Clone,Copy,PartialEq)]
+enum SplitterDrag {
+    Idle,
+    // A divider is dragged, with the distance of the cursor to the start of the divider
+    Dragging(usize, f32),
+}
+
+#[derive(Clone)]
+pub struct SplitterState {
+    direction: SplitDirection,
+    // The share of every pane in the space that is left after the dividers
+    ratios: Vec<f32>,
+    // The ratio a collapsed pane had before it was collapsed
+    collapsed: Vec<Option<f32>>,
+    // Start and size of every pane in the last frame, relative to the splitter
+    panes: Vec<(f32, f32)>,
+    drag: SplitterDrag,
+    hovered: Option<usize>,
+    last_press: Option<(usize, Instant)>,
+}
+
+// Panes separated by dividers that can be dragged to resize the panes, or double clicked
+//  to collapse the smaller of the two panes next to them.
+// The panes are added with `panes()` on the result of adding the widget.
+pub struct Splitter {
+    direction: SplitDirection,
+    ratios: Vec<f32>,
+    min: Vec<f32>,
+    max: Vec<f32>,
+}
+
+impl Splitter {
+    // A splitter with `count` panes of equal size
+    pub fn new(direction: SplitDirection, count: usize) -> Self {
+        let count = count.max(1);
+        Self {
+            direction,
+            ratios: vec![1.0 / count as f32; count],
+            min: vec![0.0; count],
+            max: vec![::std::f32::INFINITY; count],
+        }
+    }
+
+    // The initial share of every pane. The ratios are kept by the widget once it's created.
+    pub fn with_ratios(mut self, ratios: &[f32]) -> Self {
+        let total: f32 = ratios.iter().sum();
+        if ratios.len() == self.ratios.len() && total > 0.0 {
+            self.ratios = ratios.iter().map(|r| r / total).collect();
+        }
+        self
+    }
+
+    // The smallest size of the pane at `index`, indices of panes that don't exist are ignored
+    pub fn with_min_size(mut self, index: usize, size: f32) -> Self {
+        if let Some(min) = self.min.get_mut(index) {
+            *min = size;
+        }
+        self
+    }
+
+    // The largest size of the pane at `index`, indices of panes that don't exist are ignored
+    pub fn with_max_size(mut self, index: usize, size: f32) -> Self {
+        if let Some(max) = self.max.get_mut(index) {
+            *max = size;
+        }
+        self
+    }
+
+    fn count(&self) -> usize {
+        self.ratios.len()
+    }
+
+    fn cursor_style(&self) -> MouseStyle {
+        match self.direction {
+            SplitDirection::Horizontal => MouseStyle::ResizeWe,
+            SplitDirection::Vertical => MouseStyle::ResizeNs,
+        }
+    }
+
+    // Size of the splitter along the direction
+    fn length(&self, rect: &Rect) -> f32 {
+        match self.direction {
+            SplitDirection::Horizontal => rect.width(),
+            SplitDirection::Vertical => rect.height(),
+        }
+    }
+
+    // Space left for the panes after the dividers
+    fn available(&self, rect: &Rect) -> f32 {
+        (self.length(rect) - DIVIDER_SIZE * (self.count() - 1) as f32).max(0.0)
+    }
+
+    // Start and size of every pane
+    fn measure(&self, state: &SplitterState, rect: &Rect) -> Vec<(f32, f32)> {
+        let available = self.available(rect);
+
+        let mut sizes: Vec<f32> = (0..self.count()).map(|i| {
+            if state.collapsed[i].is_some() {
+                0.0
+            } else {
+                (state.ratios[i] * available).max(self.min[i]).min(self.max[i])
+            }
+        }).collect();
+
+        // the last open pane takes what is left after the limits are applied
+        let used: f32 = sizes.iter().sum();
+        if let Some(last) = (0..self.count()).rev().find(|&i| state.collapsed[i].is_none()) {
+            sizes[last] = (sizes[last] + available - used).max(0.0);
+        }
+
+        let mut start = 0.0;
+        sizes.into_iter().map(|size| {
+            let pane = (start, size);
+            start += size + DIVIDER_SIZE;
+            pane
+        }).collect()
+    }
+
+    fn divider_rect(&self, rect: &Rect, pane: (f32, f32)) -> Rect {
+        let start = pane.0 + pane.1;
+        match self.direction {
+            SplitDirection::Horizontal => Rect {
+                left: rect.left + start,
+                right: rect.left + start + DIVIDER_SIZE,
+                ..*rect
+            },
+            SplitDirection::Vertical => Rect {
+                top: rect.top + start,
+                bottom: rect.top + start + DIVIDER_SIZE,
+                ..*rect
+            },
+        }
+    }
+
+    // Collapse the smaller pane next to the divider, or restore it if one is collapsed
+    fn toggle(&self, state: &mut SplitterState, divider: usize) {
+        for &(pane, other) in [(divider, divider + 1), (divider + 1, divider)].iter() {
+            if let Some(ratio) = state.collapsed[pane].take() {
+                state.ratios[pane] = ratio;
+                state.ratios[other] = (state.ratios[other] - ratio).max(0.0);
+                return;
+            }
+        }
+
+        let (pane, other) = if state.panes[divider].1 <= state.panes[divider + 1].1 {
+            (divider, divider + 1)
+        } else {
+            (divider + 1, divider)
+        };
+        state.collapsed[pane] = Some(state.ratios[pane]);
+        state.ratios[other] += state.ratios[pane];
+        state.ratios[pane] = 0.0;
+    }
+}
+
+impl WidgetBase for Splitter {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, _style: &Style) {
+        world.create_component(id, Layout::new());
+        world.create_component(id, Drawing::new());
+        world.create_component(id, SplitterState {
+            direction: self.direction,
+            ratios: self.ratios.clone(),
+            collapsed: vec![None; self.count()],
+            panes: vec![(0.0, 0.0); self.count()],
+            drag: SplitterDrag::Idle,
+            hovered: None,
+            last_press: None,
+        });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = layout.borrow().current;
+
+        let mut state = world.component::<SplitterState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        // start over when the number of panes changes
+        if state.ratios.len() != self.count() {
+            state.ratios = self.ratios.clone();
+            state.collapsed = vec![None; self.count()];
+            state.drag = SplitterDrag::Idle;
+            state.hovered = None;
+        }
+        state.direction = self.direction;
+        self.ratios = state.ratios.clone();
+
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+        drawing.primitives.clear();
+
+        let current = match current {
+            Some(current) => current,
+            None => {
+                state.panes = vec![(0.0, 0.0); self.count()];
+                return None;
+            },
+        };
+
+        let panes = self.measure(&state, &current);
+        state.panes = panes;
+
+        let active = match state.drag {
+            SplitterDrag::Dragging(divider, _) => Some(divider),
+            SplitterDrag::Idle => state.hovered,
+        };
+        for divider in 0..self.count() - 1 {
+            let color = if active == Some(divider) {
+                Color { r: 0.2, g: 0.4, b: 0.8, a: 1.0 }
+            } else {
+                Color { r: 0.0, g: 0.0, b: 0.0, a: 0.2 }
+            };
+            drawing.primitives.push(Primitive::DrawRect(self.divider_rect(&current, state.panes[divider]), color));
+        }
+
+        input.and_then(|ir| ir.intersect(&current))
+    }
+
+    fn event(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, context: &mut EventSystemContext) {
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = match layout.borrow().current {
+            Some(current) => current,
+            None => return,
+        };
+
+        let mut state = world.component::<SplitterState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        if state.panes.len() != self.count() {
+            return;
+        }
+
+        // position of the cursor along the direction, relative to the splitter
+        let cursor = match self.direction {
+            SplitDirection::Horizontal => context.cursor.x - current.left,
+            SplitDirection::Vertical => context.cursor.y - current.top,
+        };
+
+        let hovered = if context.cursor.inside(&current) {
+            (0..self.count() - 1).find(|&divider| {
+                let start = state.panes[divider].0 + state.panes[divider].1;
+                cursor >= start - DIVIDER_GRAB && cursor <= start + DIVIDER_SIZE + DIVIDER_GRAB
+            })
+        } else {
+            None
+        };
+        state.hovered = hovered;
+
+        let drag = state.drag;
+        state.drag = match drag {
+            SplitterDrag::Idle => {
+                if hovered.is_some() {
+                    context.style = self.cursor_style();
+                }
+
+                match (&context.event, hovered) {
+                    (&Event::Press(Key::LeftMouseButton, _), Some(divider)) => {
+                        let now = Instant::now();
+                        let double = state.last_press.map_or(false, |(last, time)| {
+                            last == divider && now.duration_since(time) < Duration::from_millis(MULTI_CLICK_TIME)
+                        });
+
+                        if double {
+                            state.last_press = None;
+                            self.toggle(&mut state, divider);
+                            let panes = self.measure(&state, &current);
+                            state.panes = panes;
+                            SplitterDrag::Idle
+                        } else {
+                            state.last_press = Some((divider, now));
+                            context.capture = Capture::CaptureMouse(self.cursor_style());
+                            let start = state.panes[divider].0 + state.panes[divider].1;
+                            SplitterDrag::Dragging(divider, cursor - start)
+                        }
+                    },
+                    _ => SplitterDrag::Idle,
+                }
+            },
+            SplitterDrag::Dragging(divider, offset) => {
+                context.capture = Capture::CaptureMouse(self.cursor_style());
+
+                let (first, second) = (divider, divider + 1);
+                let combined = state.panes[first].1 + state.panes[second].1;
+
+                // keep both panes within their limits
+                let low = self.min[first].max(combined - self.max[second]);
+                let high = self.max[first].min(combined - self.min[second]);
+                let size = (cursor - offset - state.panes[first].0).min(high).max(low).max(0.0).min(combined);
+
+                let available = self.available(&current);
+                if available > 0.0 {
+                    state.collapsed[first] = None;
+                    state.collapsed[second] = None;
+                    state.ratios[first] = size / available;
+                    state.ratios[second] = (combined - size) / available;
+                    let panes = self.measure(&state, &current);
+                    state.panes = panes;
+                }
+
+                match context.event {
+                    Event::Release(Key::LeftMouseButton, _) => SplitterDrag::Idle,
+                    _ => SplitterDrag::Dragging(divider, offset),
+                }
+            },
+        };
+    }
+}
+
+impl Widget for Splitter {
+    // The share of every pane, it can be stored to restore the splitter later
+    type Result = Vec<f32>;
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result {
+        self.ratios.clone()
+    }
+}
+
+impl<'a> WidgetResult<'a, Splitter> {
+    // Add the panes. `f` is called with the index of every pane that isn't collapsed, to add
+    //  the content of the pane. The content is laid out relative to the pane with `super`.
+    pub fn panes<F: FnMut(&mut Context, usize)>(mut self, mut f: F) {
+        let (splitter, state) = {
+            let ui = &*self.context.parent;
+            (
+                ui.component::<Layout>(self.internal_id).unwrap().borrow().clone(),
+                ui.component::<SplitterState>(self.internal_id).unwrap().borrow().clone(),
+            )
+        };
+
+        for (index, &(start, size)) in state.panes.iter().enumerate() {
+            if state.collapsed[index].is_some() {
+                continue;
+            }
+
+            let mut pane = self.context.add(&index.to_string(), SplitterPane {
+                splitter: splitter.clone(),
+                direction: state.direction,
+                start,
+                size,
+            });
+            f(&mut pane.context, index);
+        }
+    }
+}
+
+#[derive(Clone,Copy)]
+struct SplitterPaneState {
+    vars: (cassowary::Variable, cassowary::Variable),
+    suggested: (f32, f32),
+}
+
+// A single pane of a `Splitter`. The position is an edit variable, like the rows of a
+//  `VirtualList`, so the pane can follow the dividers without adding new constraints.
+struct SplitterPane {
+    splitter: Layout,
+    direction: SplitDirection,
+    start: f32,
+    size: f32,
+}
+
+impl WidgetBase for SplitterPane {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, _style: &Style) {
+        let start = cassowary::Variable::new();
+        let size = cassowary::Variable::new();
+
+        let layout = Layout::new()
+            .with_constraints(|layout| match self.direction {
+                SplitDirection::Horizontal => vec![
+                    layout.left |EQ(REQUIRED)| self.splitter.left + start,
+                    layout.width |EQ(REQUIRED)| size,
+                    layout.top |EQ(REQUIRED)| self.splitter.top,
+                    layout.bottom |EQ(REQUIRED)| self.splitter.bottom,
+                ],
+                SplitDirection::Vertical => vec![
+                    layout.top |EQ(REQUIRED)| self.splitter.top + start,
+                    layout.height |EQ(REQUIRED)| size,
+                    layout.left |EQ(REQUIRED)| self.splitter.left,
+                    layout.right |EQ(REQUIRED)| self.splitter.right,
+                ],
+            })
+            .with_edit(start, &mut world.layout_solver)
+            .with_edit(size, &mut world.layout_solver);
+
+        world.layout_solver.suggest_value(start, self.start as f64).ok();
+        world.layout_solver.suggest_value(size, self.size as f64).ok();
+
+        world.create_component(id, layout);
+        world.create_component(id, Clipper::new(Rect::zero()).with_updater(|clip, layout| {
+            let current = layout.as_ref().unwrap().current.clone();
+            if current.is_some() {
+                clip.rect = current.unwrap();
+            }
+        }));
+        world.create_component(id, SplitterPaneState {
+            vars: (start, size),
+            suggested: (self.start, self.size),
+        });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let mut state = world.component::<SplitterPaneState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        if state.suggested != (self.start, self.size) {
+            state.suggested = (self.start, self.size);
+            world.layout_solver.suggest_value(state.vars.0, self.start as f64).ok();
+            world.layout_solver.suggest_value(state.vars.1, self.size as f64).ok();
+        }
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let layout = layout.borrow();
+        layout.current().and_then(|current| input.and_then(|ir| ir.intersect(current)))
+    }
+}
+
+impl Widget for SplitterPane {
+    type Result = ();
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result { }
+}
@@ -0,0 +1,486 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use super::*;
+
+// Distance from a column divider at which it can be dragged
+const DIVIDER_GRAB: f32 = 3.0;
+// Space between the edges of a cell and it's widget
+const CELL_PADDING: f32 = 2.0;
+
+pub struct Column<'a> {
+    title: &'a str,
+    width: f32,
+    min_width: f32,
+    sortable: bool,
+}
+
+impl<'a> Column<'a> {
+    pub fn new(title: &'a str, width: f32) -> Self {
+        Self {
+            title,
+            width,
+            min_width: 16.0,
+            sortable: false,
+        }
+    }
+
+    pub fn with_min_width(mut self, min_width: f32) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    // Clicking the header of the column requests sorting the table by this column
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone,Debug,PartialEq)]
+pub enum TableAction {
+    // The new selected rows
+    Select(Vec<usize>),
+    Sort(usize, SortOrder),
+}
+
+#[derive(Clone,Copy,PartialEq)]
+enum TableDrag {
+    Idle,
+    // Dragging the divider at the right of a column, with the grab offset relative to the divider
+    Resize(usize, f32),
+    // The header of a column was pressed
+    Header(usize),
+}
+
+#[derive(Clone)]
+struct TableCell {
+    id: dag::Id,
+    cons: Vec<cassowary::Constraint>,
+}
+
+#[derive(Clone)]
+pub struct TableState {
+    // Left edge and width variables of the columns
+    columns: Vec<(cassowary::Variable, cassowary::Variable)>,
+    widths: Vec<f32>,
+    // Edit variable for the height of the rows
+    content: cassowary::Variable,
+    height: f32,
+    rows: usize,
+    cells: Vec<TableCell>,
+    drag: TableDrag,
+    hovered: Option<usize>,
+    // The row that a shift click selects from
+    anchor: Option<usize>,
+    // The action is decided in event(), it is reported by the widget in the next frame.
+    pending: Option<TableAction>,
+}
+
+// A table with a header row. The children of the table are it's cells, they are placed
+//  in rows from left to right, in the order they are added.
+pub struct Table<'a> {
+    columns: &'a [Column<'a>],
+    selected: &'a [usize],
+    sort: Option<(usize, SortOrder)>,
+    multi_select: bool,
+    row_height: Option<f32>,
+    action: Option<TableAction>,
+}
+
+impl<'a> Table<'a> {
+    pub fn new(columns: &'a [Column<'a>], selected: &'a [usize]) -> Self {
+        Self {
+            columns,
+            selected,
+            sort: None,
+            multi_select: false,
+            row_height: None,
+            action: None,
+        }
+    }
+
+    // Show the sort indicator on a column
+    pub fn with_sort(mut self, sort: Option<(usize, SortOrder)>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    // Allow selecting multiple rows with ctrl and shift clicks
+    pub fn with_multi_select(mut self) -> Self {
+        self.multi_select = true;
+        self
+    }
+
+    pub fn with_row_height(mut self, height: f32) -> Self {
+        self.row_height = Some(height);
+        self
+    }
+
+    fn text(&self, style: &Style, label: &str) -> Text {
+        Text {
+            text: label.to_string(),
+            font: style.font.clone(),
+            size: 16.0,
+            wrap: TextWrap::NoWrap,
+            color: Color::black(),
+            border: None,
+            padding: Rect::zero(),
+        }
+    }
+
+    fn header_height(&self, style: &Style) -> f32 {
+        let margin = style.button_normal.margin();
+        self.text(style, "").measure(None).height() + margin.top + margin.bottom
+    }
+
+    fn row_height(&self, style: &Style) -> f32 {
+        self.row_height.unwrap_or_else(|| self.text(style, "").measure(None).height() + 8.0)
+    }
+
+    // The left and right edges of the columns
+    fn column_ranges(&self, state: &TableState, current: Rect) -> Vec<(f32, f32)> {
+        let mut left = current.left;
+        state.widths.iter().map(|&width| {
+            let range = (left, left + width);
+            left += width;
+            range
+        }).collect()
+    }
+
+    fn row_rect(&self, style: &Style, current: Rect, row: usize) -> Rect {
+        let height = self.row_height(style);
+        let top = current.top + self.header_height(style) + row as f32 * height;
+        Rect { top, bottom: top + height, ..current }
+    }
+}
+
+impl<'a> WidgetBase for Table<'a> {
+    fn tabstop(&self) -> bool {
+        true
+    }
+
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let content = cassowary::Variable::new();
+        let columns: Vec<_> = self.columns.iter()
+            .map(|_| (cassowary::Variable::new(), cassowary::Variable::new()))
+            .collect();
+        let header = self.header_height(style) as f64;
+
+        let mut layout = Layout::new()
+            .with_constraints(|layout| {
+                let mut cons = vec![
+                    layout.height |GE(STRONG)| content + header,
+                    layout.height |LE(WEAK)| content + header,
+                ];
+                let mut right = cassowary::Expression::from(layout.left);
+                for &(left, width) in columns.iter() {
+                    cons.push(left |EQ(REQUIRED)| right.clone());
+                    right = left + width;
+                }
+                cons.push(layout.right |GE(STRONG)| right.clone());
+                cons.push(layout.right |LE(WEAK)| right);
+                cons
+            })
+            .with_edit(content, &mut world.layout_solver);
+
+        for &(_, width) in columns.iter() {
+            layout = layout.with_edit(width, &mut world.layout_solver);
+        }
+
+        for (&(_, width), column) in columns.iter().zip(self.columns.iter()) {
+            world.layout_solver.suggest_value(width, column.width as f64).ok();
+        }
+
+        world.create_component(id, layout);
+        world.create_component(id, Drawing::new());
+        world.create_component(id, TableState {
+            columns,
+            widths: self.columns.iter().map(|column| column.width).collect(),
+            content,
+            height: 0.0,
+            rows: 0,
+            cells: vec![],
+            drag: TableDrag::Idle,
+            hovered: None,
+            anchor: None,
+            pending: None,
+        });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let mut state = world.component::<TableState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        self.action = state.pending.take();
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let layout = layout.borrow();
+
+        let count = state.columns.len();
+        let header = self.header_height(style);
+        let row_height = self.row_height(style);
+
+        // place the cells, constraints are only generated for cells that moved
+        let mut old_constraints = vec![];
+        let mut new_constraints = vec![];
+        let mut cells = 0;
+
+        if count > 0 {
+            for (i, &cell) in world.children().enumerate() {
+                if state.cells.get(i).map_or(false, |cached| cached.id == cell) {
+                    cells += 1;
+                    continue;
+                }
+
+                let item = world.component::<Layout>(cell).unwrap();
+                let item = item.borrow();
+
+                let (left, width) = state.columns[i % count];
+                let top = (header + (i / count) as f32 * row_height) as f64;
+                let padding = CELL_PADDING as f64;
+
+                let cons = vec![
+                    item.left |EQ(REQUIRED)| left + padding,
+                    item.right |EQ(REQUIRED)| left + width - padding,
+                    item.top |EQ(REQUIRED)| layout.top + top + padding,
+                    item.bottom |EQ(REQUIRED)| layout.top + top + row_height as f64 - padding,
+                ];
+                new_constraints.extend(cons.iter().cloned());
+
+                let cached = TableCell { id: cell, cons };
+                if i < state.cells.len() {
+                    old_constraints.extend(state.cells[i].cons.drain(..));
+                    state.cells[i] = cached;
+                } else {
+                    state.cells.push(cached);
+                }
+                cells += 1;
+            }
+        }
+
+        for cell in state.cells.split_off(cells) {
+            old_constraints.extend(cell.cons);
+        }
+        for c in old_constraints {
+            world.layout_solver.remove_constraint(&c).ok();
+        }
+        world.layout_solver.add_constraints(new_constraints.iter()).ok();
+
+        // resize to the number of rows
+        state.rows = if count > 0 { (cells + count - 1) / count } else { 0 };
+        let height = state.rows as f32 * row_height;
+        if height != state.height {
+            state.height = height;
+            world.layout_solver.suggest_value(state.content, height as f64).ok();
+        }
+
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+        drawing.primitives.clear();
+
+        let current = match layout.current {
+            Some(current) => current,
+            None => return None,
+        };
+
+        // rows are tinted on top of the cells
+        for row in 0..state.rows {
+            let rect = self.row_rect(style, current, row);
+            let color = if self.selected.contains(&row) {
+                Some(Color { r: 0.2, g: 0.4, b: 0.8, a: 0.3 })
+            } else if state.hovered == Some(row) {
+                Some(Color { r: 0.2, g: 0.4, b: 0.8, a: 0.1 })
+            } else if row % 2 == 1 {
+                Some(Color::black().with_alpha(0.05))
+            } else {
+                None
+            };
+            if let Some(color) = color {
+                drawing.primitives.push(Primitive::DrawRect(rect, color));
+            }
+        }
+
+        // header
+        let ranges = self.column_ranges(&state, current);
+        for (index, (column, &(left, right))) in self.columns.iter().zip(ranges.iter()).enumerate() {
+            let rect = Rect { left, top: current.top, right, bottom: current.top + header };
+            let patch = match state.drag {
+                TableDrag::Header(i) if i == index => &style.button_pressed,
+                _ => &style.button_normal,
+            };
+            drawing.primitives.push(Primitive::Draw9(patch.clone(), rect, Color::white()));
+
+            let content = rect.after_padding(patch.margin());
+            drawing.primitives.push(Primitive::PushClip(content));
+
+            let text = self.text(style, column.title);
+            let measured = text.measure(None);
+            let top = ((content.top + content.bottom - measured.height()) * 0.5).floor();
+            drawing.primitives.push(Primitive::DrawText(text, Rect::from_xywh(content.left, top, measured.width(), measured.height())));
+
+            if let Some((sorted, order)) = self.sort {
+                if sorted == index {
+                    let image = match order {
+                        SortOrder::Ascending => &style.sort_ascending,
+                        SortOrder::Descending => &style.sort_descending,
+                    };
+                    let size = image.size;
+                    let top = ((content.top + content.bottom - size.height()) * 0.5).floor();
+                    drawing.primitives.push(Primitive::DrawImage(
+                        image.clone(),
+                        Rect::from_xywh(content.right - size.width(), top, size.width(), size.height()),
+                        Color::white()
+                    ));
+                }
+            }
+
+            drawing.primitives.push(Primitive::PopClip);
+        }
+
+        input.and_then(|ir| ir.intersect(&current))
+    }
+
+    fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = match layout.borrow().current {
+            Some(current) => current,
+            None => return,
+        };
+
+        let mut state = world.component::<TableState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        let ranges = self.column_ranges(&state, current);
+        let header = Rect { bottom: current.top + self.header_height(style), ..current };
+
+        let divider = if context.cursor.inside(&header) {
+            ranges.iter().position(|&(_, right)| (context.cursor.x - right).abs() <= DIVIDER_GRAB)
+        } else {
+            None
+        };
+        let column = if context.cursor.inside(&header) {
+            ranges.iter().position(|&(left, right)| context.cursor.x >= left && context.cursor.x < right)
+        } else {
+            None
+        };
+        let row = (0..state.rows).find(|&row| context.cursor.inside(&self.row_rect(style, current, row)));
+
+        state.hovered = row;
+
+        state.drag = match state.drag {
+            TableDrag::Idle => {
+                if divider.is_some() {
+                    context.style = MouseStyle::ResizeWe;
+                }
+
+                match context.event {
+                    Event::Press(Key::LeftMouseButton, modifiers) => {
+                        if let Some(divider) = divider {
+                            context.capture = Capture::CaptureMouse(MouseStyle::ResizeWe);
+                            TableDrag::Resize(divider, context.cursor.x - ranges[divider].1)
+                        } else if let Some(column) = column.and_then(|c| match self.columns.get(c) {
+                            // the columns may have changed since the last layout
+                            Some(column) if column.sortable => Some(c),
+                            _ => None,
+                        }) {
+                            context.capture = Capture::CaptureMouse(MouseStyle::ArrowClicking);
+                            TableDrag::Header(column)
+                        } else if let Some(row) = row {
+                            context.capture = Capture::CaptureFocus(MouseStyle::Arrow);
+
+                            let selected = if !self.multi_select {
+                                vec![row]
+                            } else if modifiers.shift && state.anchor.is_some() {
+                                let anchor = state.anchor.unwrap();
+                                (anchor.min(row)..anchor.max(row) + 1).collect()
+                            } else if modifiers.ctrl {
+                                let mut selected: Vec<usize> = self.selected.iter()
+                                    .cloned()
+                                    .filter(|&r| r != row)
+                                    .collect();
+                                if !self.selected.contains(&row) {
+                                    selected.push(row);
+                                    selected.sort();
+                                }
+                                selected
+                            } else {
+                                vec![row]
+                            };
+
+                            if !modifiers.shift {
+                                state.anchor = Some(row);
+                            }
+                            state.pending = Some(TableAction::Select(selected));
+                            TableDrag::Idle
+                        } else {
+                            TableDrag::Idle
+                        }
+                    },
+                    // keyboard selection moves a single selected row
+                    Event::Press(Key::Up, _) if context.focused && state.rows > 0 => {
+                        let row = self.selected.iter().min().map_or(0, |&r| if r > 0 { r - 1 } else { 0 });
+                        state.anchor = Some(row);
+                        state.pending = Some(TableAction::Select(vec![row]));
+                        TableDrag::Idle
+                    },
+                    Event::Press(Key::Down, _) if context.focused && state.rows > 0 => {
+                        let row = self.selected.iter().max().map_or(0, |&r| (r + 1).min(state.rows - 1));
+                        state.anchor = Some(row);
+                        state.pending = Some(TableAction::Select(vec![row]));
+                        TableDrag::Idle
+                    },
+                    _ => TableDrag::Idle,
+                }
+            },
+            TableDrag::Resize(index, offset) => match (self.columns.get(index), ranges.get(index)) {
+                (Some(column), Some(&(left, _))) => {
+                    context.capture = Capture::CaptureMouse(MouseStyle::ResizeWe);
+
+                    let width = (context.cursor.x - offset - left).max(column.min_width);
+                    if width != state.widths[index] {
+                        state.widths[index] = width;
+                        world.layout_solver.suggest_value(state.columns[index].1, width as f64).ok();
+                    }
+
+                    match context.event {
+                        Event::Release(Key::LeftMouseButton, _) => TableDrag::Idle,
+                        _ => TableDrag::Resize(index, offset),
+                    }
+                },
+                // the resized column was removed
+                _ => TableDrag::Idle,
+            },
+            TableDrag::Header(index) => {
+                context.capture = Capture::CaptureMouse(MouseStyle::ArrowClicking);
+
+                match context.event {
+                    Event::Release(Key::LeftMouseButton, _) => {
+                        if column == Some(index) {
+                            let order = match self.sort {
+                                Some((sorted, SortOrder::Ascending)) if sorted == index => SortOrder::Descending,
+                                _ => SortOrder::Ascending,
+                            };
+                            state.pending = Some(TableAction::Sort(index, order));
+                        }
+                        TableDrag::Idle
+                    },
+                    _ => TableDrag::Header(index),
+                }
+            },
+        };
+    }
+}
+
+impl<'a> Widget for Table<'a> {
+    type Result = Option<TableAction>;
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result {
+        self.action.clone()
+    }
+}
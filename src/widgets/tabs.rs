@@ -0,0 +1,517 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use super::*;
+
+// Distance the mouse must move before a pressed tab starts dragging
+const DRAG_THRESHOLD: f32 = 4.0;
+// Space between the icon, the label and the close button
+const TAB_SPACING: f32 = 4.0;
+// Distance the mouse must move away from the tabs before a dragged tab is detached
+const DETACH_DISTANCE: f32 = 24.0;
+
+pub struct Tab<'a> {
+    label: &'a str,
+    icon: Option<Image>,
+    closable: bool,
+}
+
+impl<'a> Tab<'a> {
+    // The label identifies the tab, so it should be unique within the tabs.
+    pub fn new(label: &'a str) -> Self {
+        Self {
+            label,
+            icon: None,
+            closable: false,
+        }
+    }
+
+    pub fn with_icon(mut self, icon: Image) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    // Show a close button on the tab
+    pub fn closable(mut self) -> Self {
+        self.closable = true;
+        self
+    }
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum TabAction {
+    Select(usize),
+    Close(usize),
+    // Move the tab from the first index to the second index
+    Move(usize, usize),
+    // The tab was dragged away from the tabs
+    Detach(usize),
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct TabsResult {
+    // The selected tab, after the closed, detached and moved tabs are taken into account
+    pub selected: usize,
+    // The close button of this tab was clicked, the tab should be removed
+    pub closed: Option<usize>,
+    // This tab was dragged to a new position, the tab should be moved
+    pub moved: Option<(usize, usize)>,
+    // This tab was dragged away from the tabs, the tab should be removed
+    pub detached: Option<usize>,
+}
+
+#[derive(Clone,Copy,PartialEq)]
+enum TabsDrag {
+    Idle,
+    // A tab was pressed, with the cursor position of the press
+    Pressed(usize, f32),
+    Dragging(usize),
+    // The close button of a tab was pressed
+    Closing(usize),
+}
+
+#[derive(Clone)]
+pub struct TabsState {
+    drag: TabsDrag,
+    // The hovered tab, and if the close button of the tab is hovered
+    hovered: Option<(usize, bool)>,
+    // The rects of the tab buttons in the last frame, used for hit detection
+    rects: Vec<Rect>,
+    closable: Vec<bool>,
+    // The action is decided in event(), it is reported by the widget in the next frame.
+    pending: Option<TabAction>,
+}
+
+// A header strip of tab buttons with one visible page.
+// The page content is added with `pages()` on the result of adding the widget.
+pub struct Tabs {
+    action: Option<TabAction>,
+    detachable: bool,
+}
+
+impl Tabs {
+    pub fn new() -> Self {
+        Self {
+            action: None,
+            detachable: false,
+        }
+    }
+
+    // Allow tabs to be dragged away from the tabs, which is reported as `TabAction::Detach`
+    pub fn detachable(mut self) -> Self {
+        self.detachable = true;
+        self
+    }
+    // Check if the dragged tab is far enough away from the tabs to detach it
+    fn detached(&self, state: &TabsState, index: usize, context: &EventSystemContext) -> bool {
+        self.detachable && state.rects.get(index).map_or(false, |rect| {
+            context.cursor.y < rect.top - DETACH_DISTANCE || context.cursor.y > rect.bottom + DETACH_DISTANCE
+        })
+    }
+}
+
+fn close_rect(style: &Style, rect: Rect) -> Rect {
+    let size = style.tab_close.size;
+    let right = rect.right - style.tab_normal.margin().right;
+    let top = ((rect.top + rect.bottom - size.height()) * 0.5).floor();
+    Rect::from_xywh(right - size.width(), top, size.width(), size.height())
+}
+
+fn tab_text(style: &Style, label: &str) -> Text {
+    Text {
+        text: label.to_string(),
+        font: style.font.clone(),
+        size: 16.0,
+        wrap: TextWrap::NoWrap,
+        color: Color::black(),
+        border: None,
+        padding: Rect::zero(),
+    }
+}
+
+impl WidgetBase for Tabs {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, _style: &Style) {
+        world.create_component(id, Layout::new());
+        world.create_component(id, TabsState {
+            drag: TabsDrag::Idle,
+            hovered: None,
+            rects: vec![],
+            closable: vec![],
+            pending: None,
+        });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let mut state = world.component::<TabsState>(id).unwrap();
+        self.action = state.borrow_mut().pending.take();
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let layout = layout.borrow();
+        layout.current().and_then(|current| input.and_then(|ir| ir.intersect(current)))
+    }
+
+    fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        let mut state = world.component::<TabsState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        let hovered = state.rects.iter().position(|rect| context.cursor.inside(rect)).map(|index| {
+            let close = state.closable[index] && context.cursor.inside(&close_rect(style, state.rects[index]));
+            (index, close)
+        });
+
+        state.drag = match state.drag {
+            TabsDrag::Idle => match (context.event, hovered) {
+                (Event::Press(Key::LeftMouseButton, _), Some((index, true))) => {
+                    context.capture = Capture::CaptureFocus(MouseStyle::ArrowClicking);
+                    TabsDrag::Closing(index)
+                },
+                (Event::Press(Key::LeftMouseButton, _), Some((index, false))) => {
+                    context.capture = Capture::CaptureMouse(MouseStyle::ArrowClicking);
+                    state.pending = Some(TabAction::Select(index));
+                    TabsDrag::Pressed(index, context.cursor.x)
+                },
+                _ => TabsDrag::Idle,
+            },
+            TabsDrag::Pressed(index, x) => {
+                context.capture = Capture::CaptureMouse(MouseStyle::ArrowClicking);
+                match context.event {
+                    Event::Release(Key::LeftMouseButton, _) => TabsDrag::Idle,
+                    _ => if (context.cursor.x - x).abs() > DRAG_THRESHOLD || self.detached(&state, index, context) {
+                        TabsDrag::Dragging(index)
+                    } else {
+                        TabsDrag::Pressed(index, x)
+                    },
+                }
+            },
+            TabsDrag::Dragging(index) => {
+                context.capture = Capture::CaptureMouse(MouseStyle::ArrowClicking);
+                match context.event {
+                    Event::Release(Key::LeftMouseButton, _) => TabsDrag::Idle,
+                    _ if state.pending.is_none() && self.detached(&state, index, context) => {
+                        state.pending = Some(TabAction::Detach(index));
+                        state.rects.clear();
+                        TabsDrag::Idle
+                    },
+                    _ => {
+                        // swap with a neighbour when the cursor passes it's center. the rects are
+                        //  only valid again after the move is applied by the caller.
+                        let center = |rect: &Rect| (rect.left + rect.right) * 0.5;
+                        let count = state.rects.len();
+                        let target = if count == 0 || state.pending.is_some() {
+                            index
+                        } else if index + 1 < count && context.cursor.x > center(&state.rects[index + 1]) {
+                            index + 1
+                        } else if index > 0 && context.cursor.x < center(&state.rects[index - 1]) {
+                            index - 1
+                        } else {
+                            index
+                        };
+
+                        if target != index {
+                            state.pending = Some(TabAction::Move(index, target));
+                            state.rects.clear();
+                        }
+                        TabsDrag::Dragging(target)
+                    },
+                }
+            },
+            TabsDrag::Closing(index) => {
+                context.capture = Capture::CaptureFocus(MouseStyle::ArrowClicking);
+                match context.event {
+                    Event::Release(Key::LeftMouseButton, _) => {
+                        if hovered == Some((index, true)) {
+                            state.pending = Some(TabAction::Close(index));
+                            state.rects.clear();
+                        }
+                        TabsDrag::Idle
+                    },
+                    _ => TabsDrag::Closing(index),
+                }
+            },
+        };
+
+        state.hovered = hovered;
+    }
+}
+
+impl Widget for Tabs {
+    type Result = Option<TabAction>;
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result {
+        self.action
+    }
+}
+
+impl<'a> WidgetResult<'a, Tabs> {
+    // Add the tab buttons and the page of the selected tab. `f` is called with the index of the
+    //  tab that is shown, to add the content of it's page.
+    pub fn pages<F: FnOnce(&mut Context, usize)>(mut self, tabs: &[Tab], selected: usize, f: F) -> TabsResult {
+        let id = self.internal_id;
+        let count = tabs.len();
+        let selected = selected.min(count.saturating_sub(1));
+
+        // `selected` is the index in the list after the caller applied the action,
+        //  `shown` is the index of that tab in the current list.
+        let mut result = TabsResult { selected, closed: None, moved: None, detached: None };
+        let mut shown = if count > 0 { Some(selected) } else { None };
+
+        match self.result {
+            Some(TabAction::Select(index)) if index < count => {
+                result.selected = index;
+                shown = Some(index);
+            },
+            Some(TabAction::Close(index)) |
+            Some(TabAction::Detach(index)) if index < count => {
+                if let Some(TabAction::Close(_)) = self.result {
+                    result.closed = Some(index);
+                } else {
+                    result.detached = Some(index);
+                }
+                if index < selected {
+                    result.selected = selected - 1;
+                } else if index == selected {
+                    result.selected = index.min(count.saturating_sub(2));
+                    shown = if index + 1 < count {
+                        Some(index + 1)
+                    } else if index > 0 {
+                        Some(index - 1)
+                    } else {
+                        None
+                    };
+                }
+            },
+            Some(TabAction::Move(from, to)) if from < count && to < count => {
+                result.moved = Some((from, to));
+                result.selected = if selected == from {
+                    to
+                } else if from < selected && to >= selected {
+                    selected - 1
+                } else if from > selected && to <= selected {
+                    selected + 1
+                } else {
+                    selected
+                };
+            },
+            _ => {
+                // ctrl+tab cycles through the tabs while the focus is on the tabs or on the page
+                let ui = &*self.context.parent;
+                let focused = ui.focus.map_or(false, |focus| {
+                    focus == id || ui.tree_stack.last().map_or(false, |tree| tree.contains(focus))
+                });
+
+                if focused && count > 0 {
+                    for event in ui.events.iter() {
+                        if let Event::Press(Key::Tab, Modifiers { ctrl: true, shift, .. }) = *event {
+                            result.selected = if shift {
+                                (result.selected + count - 1) % count
+                            } else {
+                                (result.selected + 1) % count
+                            };
+                            shown = Some(result.selected);
+                        }
+                    }
+                }
+            },
+        }
+
+        let (hovered, dragging) = {
+            let state = self.context.parent.component::<TabsState>(id).unwrap();
+            let state = state.borrow();
+            let dragging = match state.drag {
+                TabsDrag::Pressed(index, _) | TabsDrag::Dragging(index) => Some(index),
+                _ => None,
+            };
+            (state.hovered, dragging)
+        };
+
+        let mut rects = vec![];
+        let header_id = {
+            let mut header = self.context.add("header", Collection::new(LeftToRightLayout::new(ContentAlign::Stretching)));
+
+            for (index, tab) in tabs.iter().enumerate() {
+                if result.closed == Some(index) || result.detached == Some(index) {
+                    continue;
+                }
+
+                let button = header.context.add(tab.label, TabButton {
+                    label: tab.label.to_string(),
+                    icon: tab.icon.clone(),
+                    closable: tab.closable,
+                    selected: shown == Some(index),
+                    hovered: hovered.map(|(i, _)| i) == Some(index) || dragging == Some(index),
+                    close_hovered: hovered == Some((index, true)),
+                });
+
+                let layout = button.context.parent.component::<Layout>(button.internal_id).unwrap();
+                let current = layout.borrow().current.unwrap_or(Rect::zero());
+                rects.push((current, tab.closable));
+            }
+
+            header.internal_id
+        };
+
+        // the tab positions change after a close or move, so the old rects can't be used anymore
+        if result.closed.is_none() && result.moved.is_none() && result.detached.is_none() {
+            let mut state = self.context.parent.component::<TabsState>(id).unwrap();
+            let mut state = state.borrow_mut();
+            state.closable = rects.iter().map(|&(_, closable)| closable).collect();
+            state.rects = rects.into_iter().map(|(rect, _)| rect).collect();
+        }
+
+        if let Some(index) = shown {
+            let (tabs_layout, header_layout) = {
+                let ui = &*self.context.parent;
+                (
+                    ui.component::<Layout>(id).unwrap().borrow().clone(),
+                    ui.component::<Layout>(header_id).unwrap().borrow().clone(),
+                )
+            };
+
+            // every tab has it's own page, so the widgets on different pages don't share state
+            let mut page = self.context.add(&format!("page {}", tabs[index].label), TabPage {
+                parent: tabs_layout,
+                header: header_layout,
+            });
+            f(&mut page.context, index);
+        }
+
+        self.context.rules(|var| vec![
+            var("header.left") |EQ(REQUIRED)| var("super.margin_left"),
+            var("header.right") |LE(REQUIRED)| var("super.margin_right"),
+            var("header.top") |EQ(REQUIRED)| var("super.margin_top"),
+        ]);
+
+        result
+    }
+}
+
+struct TabButton {
+    label: String,
+    icon: Option<Image>,
+    closable: bool,
+    selected: bool,
+    hovered: bool,
+    close_hovered: bool,
+}
+
+impl WidgetBase for TabButton {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let margin = style.tab_normal.margin();
+        let text = tab_text(style, &self.label).measure(None);
+
+        let mut width = text.width();
+        let mut height = text.height();
+        if let Some(ref icon) = self.icon {
+            width += icon.size.width() + TAB_SPACING;
+            height = height.max(icon.size.height());
+        }
+        if self.closable {
+            width += style.tab_close.size.width() + TAB_SPACING;
+            height = height.max(style.tab_close.size.height());
+        }
+
+        world.create_component(id, Layout::new()
+            .with_margins(margin)
+            .with_intrinsic_size_constraints(
+                width + margin.left + margin.right,
+                height + margin.top + margin.bottom,
+                500.0
+            ));
+        world.create_component(id, Drawing::new());
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, _input: Option<Rect>) -> Option<Rect> {
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = layout.borrow().current;
+
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+        drawing.primitives.clear();
+
+        let current = match current {
+            Some(current) => current,
+            None => return None,
+        };
+
+        let patch = if self.selected {
+            &style.tab_selected
+        } else if self.hovered {
+            &style.tab_hover
+        } else {
+            &style.tab_normal
+        };
+        drawing.primitives.push(Primitive::Draw9(patch.clone(), current, Color::white()));
+
+        let content = current.after_padding(patch.margin());
+        let mut left = content.left;
+
+        if let Some(ref icon) = self.icon {
+            let size = icon.size;
+            let top = ((content.top + content.bottom - size.height()) * 0.5).floor();
+            drawing.primitives.push(Primitive::DrawImage(
+                icon.clone(),
+                Rect::from_xywh(left, top, size.width(), size.height()),
+                Color::white()
+            ));
+            left += size.width() + TAB_SPACING;
+        }
+
+        let text = tab_text(style, &self.label);
+        let measured = text.measure(None);
+        let top = ((content.top + content.bottom - measured.height()) * 0.5).floor();
+        drawing.primitives.push(Primitive::DrawText(text, Rect::from_xywh(left, top, measured.width(), measured.height())));
+
+        if self.closable {
+            let color = if self.close_hovered {
+                Color::white()
+            } else {
+                Color::white().with_alpha(0.5)
+            };
+            drawing.primitives.push(Primitive::DrawImage(style.tab_close.clone(), close_rect(style, current), color));
+        }
+
+        None
+    }
+}
+
+impl Widget for TabButton {
+    type Result = ();
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result { }
+}
+
+// The area below the tab buttons, it clips the content of the page.
+struct TabPage {
+    parent: Layout,
+    header: Layout,
+}
+
+impl WidgetBase for TabPage {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, _style: &Style) {
+        let layout = Layout::new().with_constraints(|layout| vec![
+            layout.top |EQ(REQUIRED)| self.header.bottom,
+            layout.left |EQ(REQUIRED)| self.parent.margin_left,
+            layout.right |EQ(REQUIRED)| self.parent.margin_right,
+            layout.bottom |EQ(REQUIRED)| self.parent.margin_bottom,
+        ]);
+
+        world.create_component(id, layout);
+        world.create_component(id, Clipper::new(Rect::zero()).with_updater(|clip, layout| {
+            let current = layout.as_ref().unwrap().current.clone();
+            if current.is_some() {
+                clip.rect = current.unwrap();
+            }
+        }));
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let layout = world.component::<Layout>(id).unwrap();
+        let layout = layout.borrow();
+        layout.current().and_then(|current| input.and_then(|ir| ir.intersect(current)))
+    }
+}
+
+impl Widget for TabPage {
+    type Result = ();
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result { }
+}
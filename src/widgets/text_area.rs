@@ -0,0 +1,84 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use super::*;
+
+#[derive(Clone,Copy)]
+struct TextAreaState {
+    // Edit variable for the height of the text
+    content: cassowary::Variable,
+    height: f32,
+}
+
+// A multi-line input field with word wrap. The field grows with it's text, so it's meant to be
+//  put in a `Scroll` with a vertical bar. The scroll keeps the caret in view while typing.
+pub struct TextArea<'a> {
+    input: Input<'a>,
+}
+
+impl<'a> TextArea<'a> {
+    pub fn new(text: &'a mut String) -> Self {
+        Self {
+            input: Input::multiline(text),
+        }
+    }
+}
+
+impl<'a> WidgetBase for TextArea<'a> {
+    fn tabstop(&self) -> bool {
+        true
+    }
+
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let content = cassowary::Variable::new();
+
+        let layout = Layout::new()
+            .with_intrinsic_size_constraints(256.0, 128.0, 250.0)
+            .with_constraints(|layout| vec![
+                layout.height |GE(REQUIRED)| content,
+            ])
+            .with_edit(content, &mut world.layout_solver);
+
+        self.input.create_with_layout(id, world, style, layout);
+        world.create_component(id, TextAreaState { content, height: 0.0 });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let current = world.component::<Layout>(id).unwrap().borrow().current;
+
+        if let Some(current) = current {
+            let content = style.input.content_rect(current);
+            let padding = current.height() - content.height();
+
+            // grow with the text, the width is given by the parent
+            let height = {
+                let text = world.component::<Text>(id).unwrap();
+                let measured = text.borrow().measure(Some(content)).height();
+                measured + padding
+            };
+
+            let mut state = world.component::<TextAreaState>(id).unwrap();
+            let mut state = state.borrow_mut();
+            if height != state.height {
+                state.height = height;
+                world.layout_solver.suggest_value(state.content, height as f64).ok();
+            }
+        }
+
+        self.input.update(id, world, style, input)
+    }
+
+    fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        let current = match world.component::<Layout>(id).unwrap().borrow().current {
+            Some(current) => current,
+            None => return,
+        };
+
+        self.input.edit(id, world, style, context, current);
+    }
+}
+
+impl<'a> Widget for TextArea<'a> {
+    type Result = ();
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result { }
+}
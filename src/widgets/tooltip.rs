@@ -0,0 +1,56 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use super::*;
+
+// Offset of the tooltip relative to the cursor
+const CURSOR_OFFSET: f32 = 16.0;
+
+// A tooltip is a frame that follows the cursor. It is sized by it's content and it will
+//  always be kept inside the viewport.
+// It is normally not added directly, use `WidgetResult::tooltip` or `WidgetResult::tooltip_with`.
+pub struct Tooltip { }
+
+impl Tooltip {
+    pub fn new() -> Self {
+        Self { }
+    }
+}
+
+impl WidgetBase for Tooltip {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        let layout = Layout::new();
+        let (left, top) = (layout.left, layout.top);
+
+        let layout = layout
+            .with_margins(style.tooltip.margin())
+            .with_constraints(|layout| vec![
+                layout.top |GE(REQUIRED)| world.viewport_top,
+                layout.left |GE(REQUIRED)| world.viewport_left,
+                layout.bottom |LE(REQUIRED)| world.viewport_bottom,
+                layout.right |LE(REQUIRED)| world.viewport_right,
+            ])
+            .with_edit(left, &mut world.layout_solver)
+            .with_edit(top, &mut world.layout_solver);
+
+        world.create_component(id, layout);
+        world.create_component(id, WidgetBackground::new(Background::Patch(style.tooltip.clone(), 1.0)));
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, _input: Option<Rect>) -> Option<Rect> {
+        let layout = world.component::<Layout>(id).unwrap();
+        let layout = layout.borrow();
+
+        let s = &mut world.layout_solver;
+        s.suggest_value(layout.left, (world.cursor.0 + CURSOR_OFFSET) as f64).ok();
+        s.suggest_value(layout.top, (world.cursor.1 + CURSOR_OFFSET) as f64).ok();
+
+        // tooltips are not interactive
+        None
+    }
+}
+
+impl Widget for Tooltip {
+    type Result = ();
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result { }
+}
@@ -0,0 +1,379 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use super::*;
+use std::collections::HashSet;
+
+// Horizontal offset of every level in the tree, this is also the width of the expand arrow
+const INDENT: f32 = 16.0;
+// Space between the icon and the label
+const ICON_SPACING: f32 = 4.0;
+
+// The indices of an item and it's parents, starting at the root
+pub type TreePath = SmallVec<[usize; 8]>;
+
+pub struct TreeItem<'a, T: 'a> {
+    value: T,
+    label: &'a str,
+    icon: Option<Image>,
+    children: Vec<TreeItem<'a, T>>,
+}
+
+impl<'a, T: 'a> TreeItem<'a, T> {
+    pub fn new(value: T, label: &'a str) -> Self {
+        Self {
+            value,
+            label,
+            icon: None,
+            children: vec![],
+        }
+    }
+
+    pub fn with_icon(mut self, icon: Image) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<TreeItem<'a, T>>) -> Self {
+        self.children = children;
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct TreeViewState {
+    // Expanded items, by path. This is kept while the items are rebuilt every frame.
+    expanded: HashSet<TreePath>,
+    // Edit variables for the size of the visible rows
+    content: (cassowary::Variable, cassowary::Variable),
+    size: (f32, f32),
+    hovered: Option<usize>,
+    // The selection is decided in event(), it is reported by the widget in the next frame.
+    pending: Option<TreePath>,
+}
+
+struct Row<'b, 'a: 'b, T: 'a> {
+    item: &'b TreeItem<'a, T>,
+    path: TreePath,
+    expanded: bool,
+}
+
+pub struct TreeView<'a, T: 'a + Clone + PartialEq> {
+    items: &'a [TreeItem<'a, T>],
+    selected: Option<T>,
+    size: f32,
+    result: Option<T>,
+}
+
+impl<'a, T: 'a + Clone + PartialEq> TreeView<'a, T> {
+    pub fn new(items: &'a [TreeItem<'a, T>], selected: Option<T>) -> Self {
+        Self {
+            items,
+            selected,
+            size: 16.0,
+            result: None,
+        }
+    }
+
+    pub fn with_text_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    fn text(&self, style: &Style, label: &str) -> Text {
+        Text {
+            text: label.to_string(),
+            font: style.font.clone(),
+            size: self.size,
+            wrap: TextWrap::NoWrap,
+            color: Color::black(),
+            border: None,
+            padding: Rect::zero(),
+        }
+    }
+
+    fn row_height(&self, style: &Style) -> f32 {
+        self.text(style, "").measure(None).height() + 4.0
+    }
+
+    // The visible items, in the order they are shown
+    fn rows<'b>(&'b self, expanded: &HashSet<TreePath>) -> Vec<Row<'b, 'a, T>> {
+        fn visit<'b, 'a: 'b, T: 'a>(
+            items: &'b [TreeItem<'a, T>],
+            path: &mut TreePath,
+            expanded: &HashSet<TreePath>,
+            rows: &mut Vec<Row<'b, 'a, T>>
+        ) {
+            for (index, item) in items.iter().enumerate() {
+                path.push(index);
+                let is_expanded = !item.children.is_empty() && expanded.contains(path);
+                rows.push(Row { item, path: path.clone(), expanded: is_expanded });
+                if is_expanded {
+                    visit(&item.children, path, expanded, rows);
+                }
+                path.pop();
+            }
+        }
+
+        let mut rows = vec![];
+        visit(self.items, &mut TreePath::new(), expanded, &mut rows);
+        rows
+    }
+
+    fn item(&self, path: &TreePath) -> Option<&TreeItem<'a, T>> {
+        let mut items = self.items;
+        let mut found = None;
+        for &index in path.iter() {
+            found = items.get(index);
+            items = match found {
+                Some(item) => &item.children,
+                None => return None,
+            };
+        }
+        found
+    }
+
+    fn row_rect(&self, style: &Style, current: Rect, index: usize) -> Rect {
+        let height = self.row_height(style);
+        let top = current.top + index as f32 * height;
+        Rect { top, bottom: top + height, ..current }
+    }
+
+    fn arrow_rect(&self, row: Rect, depth: usize) -> Rect {
+        let left = row.left + (depth - 1) as f32 * INDENT;
+        Rect { left, right: left + INDENT, ..row }
+    }
+}
+
+impl<'a, T: 'a + Clone + PartialEq> WidgetBase for TreeView<'a, T> {
+    fn tabstop(&self) -> bool {
+        true
+    }
+
+    fn create(&mut self, id: dag::Id, world: &mut Ui, _style: &Style) {
+        let content_w = cassowary::Variable::new();
+        let content_h = cassowary::Variable::new();
+
+        let layout = Layout::new()
+            .with_constraints(|layout| vec![
+                layout.width |GE(STRONG)| content_w,
+                layout.height |GE(STRONG)| content_h,
+                layout.width |LE(WEAK)| content_w,
+                layout.height |LE(WEAK)| content_h,
+            ])
+            .with_edit(content_w, &mut world.layout_solver)
+            .with_edit(content_h, &mut world.layout_solver);
+
+        world.create_component(id, layout);
+        world.create_component(id, Drawing::new());
+        world.create_component(id, TreeViewState {
+            expanded: HashSet::new(),
+            content: (content_w, content_h),
+            size: (0.0, 0.0),
+            hovered: None,
+            pending: None,
+        });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let mut state = world.component::<TreeViewState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        // selections are made in event(), so they are reported in the next frame
+        self.result = state.pending.take().and_then(|path| self.item(&path).map(|item| item.value.clone()));
+        if let Some(ref value) = self.result {
+            self.selected = Some(value.clone());
+        }
+
+        let rows = self.rows(&state.expanded);
+        let row_height = self.row_height(style);
+
+        // resize to the visible rows
+        let size = rows.iter().fold((0.0f32, 0.0f32), |(width, height), row| {
+            let mut right = row.path.len() as f32 * INDENT + self.text(style, row.item.label).measure(None).width();
+            if let Some(ref icon) = row.item.icon {
+                right += icon.size.width() + ICON_SPACING;
+            }
+            (width.max(right), height + row_height)
+        });
+
+        if size != state.size {
+            state.size = size;
+            world.layout_solver.suggest_value(state.content.0, size.0 as f64).ok();
+            world.layout_solver.suggest_value(state.content.1, size.1 as f64).ok();
+        }
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = layout.borrow().current;
+
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+        drawing.primitives.clear();
+
+        let current = match current {
+            Some(current) => current,
+            None => return None,
+        };
+
+        for (index, row) in rows.iter().enumerate() {
+            let rect = self.row_rect(style, current, index);
+
+            // only draw the rows that are visible, without an input rect the clip limits them
+            if input.map_or(false, |input| rect.intersect(&input).is_none()) {
+                continue;
+            }
+
+            let mut text = self.text(style, row.item.label);
+            if self.selected.as_ref() == Some(&row.item.value) {
+                drawing.primitives.push(Primitive::DrawRect(rect, Color { r: 0.2, g: 0.4, b: 0.8, a: 1.0 }));
+                text.color = Color::white();
+            } else if state.hovered == Some(index) {
+                drawing.primitives.push(Primitive::DrawRect(rect, Color { r: 0.2, g: 0.4, b: 0.8, a: 0.2 }));
+            }
+
+            let arrow = self.arrow_rect(rect, row.path.len());
+            if !row.item.children.is_empty() {
+                let image = if row.expanded { &style.tree_expanded } else { &style.tree_collapsed };
+                let size = image.size;
+                let left = ((arrow.left + arrow.right - size.width()) * 0.5).floor();
+                let top = ((arrow.top + arrow.bottom - size.height()) * 0.5).floor();
+                drawing.primitives.push(Primitive::DrawImage(
+                    image.clone(),
+                    Rect::from_xywh(left, top, size.width(), size.height()),
+                    Color::white()
+                ));
+            }
+
+            let mut left = arrow.right;
+            if let Some(ref icon) = row.item.icon {
+                let size = icon.size;
+                let top = ((rect.top + rect.bottom - size.height()) * 0.5).floor();
+                drawing.primitives.push(Primitive::DrawImage(
+                    icon.clone(),
+                    Rect::from_xywh(left, top, size.width(), size.height()),
+                    Color::white()
+                ));
+                left += size.width() + ICON_SPACING;
+            }
+
+            let measured = text.measure(None);
+            let top = ((rect.top + rect.bottom - measured.height()) * 0.5).floor();
+            drawing.primitives.push(Primitive::DrawText(text, Rect::from_xywh(left, top, measured.width(), measured.height())));
+        }
+
+        None
+    }
+
+    fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = match layout.borrow().current {
+            Some(current) => current,
+            None => return,
+        };
+
+        let mut state = world.component::<TreeViewState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        let (hovered, selected, toggle) = {
+            let rows = self.rows(&state.expanded);
+            let row_height = self.row_height(style);
+
+            let hovered = if context.cursor.inside(&current) {
+                let index = ((context.cursor.y - current.top) / row_height).floor();
+                if index >= 0.0 && (index as usize) < rows.len() {
+                    Some(index as usize)
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            // the row with the selected value, a pending selection is not reported yet
+            let index = match state.pending {
+                Some(ref pending) => rows.iter().position(|row| row.path == *pending),
+                None => self.selected.as_ref().and_then(|selected| {
+                    rows.iter().position(|row| row.item.value == *selected)
+                }),
+            };
+
+            let expandable = |index: usize| !rows[index].item.children.is_empty();
+            let count = rows.len();
+
+            // returns the row to select and the row to expand or collapse
+            let (select, toggle) = match context.event {
+                Event::Press(Key::LeftMouseButton, _) => match hovered {
+                    Some(hovered) => {
+                        context.capture = Capture::CaptureFocus(MouseStyle::ArrowClicking);
+                        let rect = self.row_rect(style, current, hovered);
+                        let arrow = self.arrow_rect(rect, rows[hovered].path.len());
+                        if expandable(hovered) && context.cursor.inside(&arrow) {
+                            (None, Some(hovered))
+                        } else {
+                            (Some(hovered), None)
+                        }
+                    },
+                    None => (None, None),
+                },
+                Event::Press(Key::Up, _) if context.focused && count > 0 => {
+                    (Some(index.map_or(0, |i| if i > 0 { i - 1 } else { 0 })), None)
+                },
+                Event::Press(Key::Down, _) if context.focused && count > 0 => {
+                    (Some(index.map_or(0, |i| (i + 1).min(count - 1))), None)
+                },
+                Event::Press(Key::Home, _) if context.focused && count > 0 => {
+                    (Some(0), None)
+                },
+                Event::Press(Key::End, _) if context.focused && count > 0 => {
+                    (Some(count - 1), None)
+                },
+                Event::Press(Key::Left, _) if context.focused => match index {
+                    // collapse the item, or go to the parent if it is already collapsed
+                    Some(i) if rows[i].expanded => (None, Some(i)),
+                    Some(i) => {
+                        let parent = &rows[i].path[..rows[i].path.len() - 1];
+                        (rows.iter().position(|row| &row.path[..] == parent), None)
+                    },
+                    None => (None, None),
+                },
+                Event::Press(Key::Right, _) if context.focused => match index {
+                    // expand the item, or go to the first child if it is already expanded
+                    Some(i) if rows[i].expanded => (Some(i + 1), None),
+                    Some(i) if expandable(i) => (None, Some(i)),
+                    _ => (None, None),
+                },
+                Event::Press(Key::Enter, _) |
+                Event::Press(Key::Space, _) if context.focused => match index {
+                    Some(i) if expandable(i) => (None, Some(i)),
+                    _ => (None, None),
+                },
+                _ => (None, None),
+            };
+
+            (
+                hovered,
+                select.map(|i| rows[i].path.clone()),
+                toggle.map(|i| rows[i].path.clone()),
+            )
+        };
+
+        state.hovered = hovered;
+
+        if selected.is_some() {
+            state.pending = selected;
+        }
+
+        if let Some(path) = toggle {
+            if !state.expanded.remove(&path) {
+                state.expanded.insert(path);
+            }
+        }
+    }
+}
+
+impl<'a, T: 'a + Clone + PartialEq> Widget for TreeView<'a, T> {
+    type Result = Option<T>;
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result {
+        self.result.clone()
+    }
+}
@@ -0,0 +1,216 @@
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+use super::*;
+
+// Number of rows that are kept alive above and below the visible area
+const OVERSCAN: usize = 1;
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct VisibleRow {
+    pub index: usize,
+    // Offset of the row from the top of the list
+    pub top: f32,
+    pub height: f32,
+}
+
+#[derive(Clone,Copy)]
+struct VirtualListState {
+    // Edit variable for the height of all rows
+    content: cassowary::Variable,
+    height: f32,
+}
+
+enum RowHeight<'a> {
+    Fixed(f32),
+    Measured(Box<Fn(usize) -> f32 + 'a>),
+}
+
+// A list that only creates widgets for the rows that are visible in the enclosing `Scroll`.
+// The rows are added with `rows()` on the result of adding the widget.
+pub struct VirtualList<'a> {
+    count: usize,
+    height: RowHeight<'a>,
+    visible: Vec<VisibleRow>,
+}
+
+impl<'a> VirtualList<'a> {
+    // A list where every row has the same height
+    pub fn new(count: usize, row_height: f32) -> Self {
+        Self {
+            count,
+            height: RowHeight::Fixed(row_height),
+            visible: vec![],
+        }
+    }
+
+    // A list where the height of every row is given by `row_height`.
+    // The heights of all rows are summed every frame, so it should be cheap.
+    pub fn measured<F: 'a + Fn(usize) -> f32>(count: usize, row_height: F) -> Self {
+        Self {
+            count,
+            height: RowHeight::Measured(Box::new(row_height)),
+            visible: vec![],
+        }
+    }
+
+    // Find the rows between the offsets `from` and `to`, and the height of all rows
+    fn rows(&self, from: f32, to: f32) -> (Vec<VisibleRow>, f32) {
+        let mut rows = vec![];
+
+        match self.height {
+            RowHeight::Fixed(height) => {
+                if height > 0.0 && to > from {
+                    let first = ((from / height).floor().max(0.0) as usize).saturating_sub(OVERSCAN);
+                    let last = ((to / height).ceil().max(0.0) as usize + OVERSCAN).min(self.count);
+                    for index in first..last {
+                        rows.push(VisibleRow { index, top: index as f32 * height, height });
+                    }
+                }
+                (rows, self.count as f32 * height)
+            },
+            RowHeight::Measured(ref row_height) => {
+                let mut tops = Vec::with_capacity(self.count + 1);
+                let mut top = 0.0;
+                for index in 0..self.count {
+                    tops.push(top);
+                    top += row_height(index);
+                }
+                tops.push(top);
+
+                if to > from {
+                    let first = (0..self.count).find(|&i| tops[i + 1] > from).unwrap_or(self.count);
+                    let last = (first..self.count).find(|&i| tops[i] >= to).unwrap_or(self.count);
+                    for index in first.saturating_sub(OVERSCAN)..(last + OVERSCAN).min(self.count) {
+                        rows.push(VisibleRow { index, top: tops[index], height: tops[index + 1] - tops[index] });
+                    }
+                }
+                (rows, top)
+            },
+        }
+    }
+}
+
+impl<'a> WidgetBase for VirtualList<'a> {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, _style: &Style) {
+        let content = cassowary::Variable::new();
+
+        let layout = Layout::new()
+            .with_constraints(|layout| vec![
+                layout.height |GE(STRONG)| content,
+                layout.height |LE(WEAK)| content,
+            ])
+            .with_edit(content, &mut world.layout_solver);
+
+        world.create_component(id, layout);
+        world.create_component(id, VirtualListState { content, height: 0.0 });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = layout.borrow().current;
+
+        // the part of the list that is visible in the enclosing scroll
+        let (from, to) = match (current, world.visible_rect()) {
+            (Some(current), Some(visible)) => (visible.top - current.top, visible.bottom - current.top),
+            _ => (0.0, 0.0),
+        };
+
+        let (visible, height) = self.rows(from, to);
+        self.visible = visible;
+
+        let mut state = world.component::<VirtualListState>(id).unwrap();
+        let mut state = state.borrow_mut();
+        if height != state.height {
+            state.height = height;
+            world.layout_solver.suggest_value(state.content, height as f64).ok();
+        }
+
+        current.and_then(|current| input.and_then(|ir| ir.intersect(&current)))
+    }
+}
+
+impl<'a> Widget for VirtualList<'a> {
+    type Result = Vec<VisibleRow>;
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result {
+        self.visible.clone()
+    }
+}
+
+impl<'a, 'b: 'a> WidgetResult<'a, VirtualList<'b>> {
+    // Add the visible rows. `f` is called with the index of every visible row, to add the
+    //  content of the row. The content is laid out relative to the row with `super`.
+    pub fn rows<F: FnMut(&mut Context, usize)>(mut self, mut f: F) {
+        let list = self.context.parent.component::<Layout>(self.internal_id).unwrap().borrow().clone();
+
+        for row in self.result.iter() {
+            let mut item = self.context.add(&row.index.to_string(), VirtualRow {
+                list: list.clone(),
+                top: row.top,
+                height: row.height,
+            });
+            f(&mut item.context, row.index);
+        }
+    }
+}
+
+#[derive(Clone,Copy)]
+struct VirtualRowState {
+    vars: (cassowary::Variable, cassowary::Variable),
+    suggested: (f32, f32),
+}
+
+// A single row of a `VirtualList`. The position is an edit variable, so rows keep their
+//  widgets when the rows above them change height.
+struct VirtualRow {
+    list: Layout,
+    top: f32,
+    height: f32,
+}
+
+impl WidgetBase for VirtualRow {
+    fn create(&mut self, id: dag::Id, world: &mut Ui, _style: &Style) {
+        let top = cassowary::Variable::new();
+        let height = cassowary::Variable::new();
+
+        let layout = Layout::new()
+            .with_constraints(|layout| vec![
+                layout.left |EQ(REQUIRED)| self.list.margin_left,
+                layout.right |EQ(REQUIRED)| self.list.margin_right,
+                layout.top |EQ(REQUIRED)| self.list.top + top,
+                layout.height |EQ(REQUIRED)| height,
+            ])
+            .with_edit(top, &mut world.layout_solver)
+            .with_edit(height, &mut world.layout_solver);
+
+        world.layout_solver.suggest_value(top, self.top as f64).ok();
+        world.layout_solver.suggest_value(height, self.height as f64).ok();
+
+        world.create_component(id, layout);
+        world.create_component(id, VirtualRowState {
+            vars: (top, height),
+            suggested: (self.top, self.height),
+        });
+    }
+
+    fn update(&mut self, id: dag::Id, world: &mut Ui, _style: &Style, input: Option<Rect>) -> Option<Rect> {
+        let mut state = world.component::<VirtualRowState>(id).unwrap();
+        let mut state = state.borrow_mut();
+
+        if state.suggested != (self.top, self.height) {
+            state.suggested = (self.top, self.height);
+            world.layout_solver.suggest_value(state.vars.0, self.top as f64).ok();
+            world.layout_solver.suggest_value(state.vars.1, self.height as f64).ok();
+        }
+
+        let layout = world.component::<Layout>(id).unwrap();
+        let layout = layout.borrow();
+        layout.current().and_then(|current| input.and_then(|ir| ir.intersect(current)))
+    }
+}
+
+impl Widget for VirtualRow {
+    type Result = ();
+
+    fn result(&mut self, _id: dag::Id) -> Self::Result { }
+}
@@ -1,6 +1,31 @@
 use super::*;
 use cassowary::strength::*;
 use cassowary::WeightedRelation::*;
+use std::time::{Duration, Instant};
+
+// Space between the parts of the title bar
+const TITLE_SPACING: f32 = 4.0;
+// Distance in pixels within which a dragged window edge snaps to another edge
+const SNAP_DISTANCE: f32 = 8.0;
+// Maximum time between the clicks of a double click on the title bar, in milliseconds
+const DOUBLE_CLICK_TIME: u64 = 500;
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum WindowButton {
+    Close,
+    Collapse,
+}
+
+// Where a window opens
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum WindowPlacement {
+    Position(f32, f32),
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
 
 #[derive(Clone)]
 pub enum WindowState {
@@ -9,49 +34,282 @@ pub enum WindowState {
     HoverFrame(MouseStyle),
     Drag(f32, f32),
     Resize(MouseStyle),
+    Pressed(WindowButton),
+}
+
+#[derive(Clone,Copy)]
+struct WindowChrome {
+    collapsed: bool,
+    // Edit variable for the part of the content that is below the collapsed window
+    overflow: cassowary::Variable,
+    // Height of the window before it was collapsed
+    expanded: f32,
+    hovered: Option<WindowButton>,
+    // The close button was clicked, the window reports that it's closed in the next frame
+    closing: bool,
+    // The window was moved to it's placement, this waits for the first layout when it
+    //  depends on the size of the window
+    placed: bool,
+    // The rect to restore when the window is maximized
+    restore: Option<Rect>,
+    last_click: Option<Instant>,
+}
+
+// The offset that moves the closest of `edges` onto one of `targets`, if it's within snapping
+//  distance
+fn snap(edges: &[f32], targets: &[f32]) -> f32 {
+    let mut offset = 0.0;
+    let mut distance = SNAP_DISTANCE;
+    for edge in edges {
+        for target in targets {
+            if (target - edge).abs() < distance {
+                distance = (target - edge).abs();
+                offset = target - edge;
+            }
+        }
+    }
+    offset
+}
+
+// The vertical and horizontal edges that `rect` can snap to. Edges of other windows only count
+//  when the windows are next to each other.
+fn snap_targets(viewport: Rect, others: &[Rect], rect: Rect) -> (Vec<f32>, Vec<f32>) {
+    let mut xs = vec![viewport.left, viewport.right];
+    let mut ys = vec![viewport.top, viewport.bottom];
+    for other in others {
+        if other.top < rect.bottom + SNAP_DISTANCE && other.bottom > rect.top - SNAP_DISTANCE {
+            xs.push(other.left);
+            xs.push(other.right);
+        }
+        if other.left < rect.right + SNAP_DISTANCE && other.right > rect.left - SNAP_DISTANCE {
+            ys.push(other.top);
+            ys.push(other.bottom);
+        }
+    }
+    (xs, ys)
 }
 
 pub struct Window {
     min_size: Rect,
+    max_size: Option<Rect>,
+    placement: WindowPlacement,
+    auto_size: bool,
     open: bool,
 
     draggable: bool,
     resizable: bool,
+
+    title: Option<String>,
+    closable: bool,
+    collapsible: bool,
 }
 
 impl Window {
+    // The window opens at the position of `min_size`
     pub fn new(min_size: Rect, open: bool) -> Self {
         Self {
             min_size, 
+            max_size: None,
+            placement: WindowPlacement::Position(min_size.left, min_size.top),
+            auto_size: false,
             open,
             draggable: true,
             resizable: true,
+            title: None,
+            closable: false,
+            collapsible: false,
         }
     }
 
     pub fn sized(width: f32, height: f32) -> Self {
         Self {
-            min_size: Rect::from_wh(width, height).translate(100.0, 100.0), 
+            min_size: Rect::from_wh(width, height), 
+            max_size: None,
+            placement: WindowPlacement::Position(100.0, 100.0),
+            auto_size: false,
             open: true,
             draggable: true,
             resizable: true,
+            title: None,
+            closable: false,
+            collapsible: false,
+        }
+    }
+
+    // The window can't be resized by dragging it's frame
+    pub fn fixed(mut self) -> Self {
+        self.resizable = false;
+        self
+    }
+
+    // The window can't be moved by dragging it
+    pub fn immovable(mut self) -> Self {
+        self.draggable = false;
+        self
+    }
+
+    pub fn with_max_size(mut self, width: f32, height: f32) -> Self {
+        self.max_size = Some(Rect::from_wh(width, height));
+        self
+    }
+
+    pub fn at(mut self, x: f32, y: f32) -> Self {
+        self.placement = WindowPlacement::Position(x, y);
+        self
+    }
+
+    pub fn placed(mut self, placement: WindowPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    pub fn centered(self) -> Self {
+        self.placed(WindowPlacement::Center)
+    }
+
+    // Size the window to the constraints of it's content instead of `min_size`. An auto sized
+    //  window can't be resized by the user.
+    pub fn auto_sized(mut self) -> Self {
+        self.auto_size = true;
+        self.resizable = false;
+        self
+    }
+
+    // Show a title bar. The window can only be dragged by it's title bar when it has one.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    // Show a close button in the title bar. The result of the window is false after it's clicked.
+    pub fn closable(mut self) -> Self {
+        self.closable = true;
+        self
+    }
+
+    // Show a button in the title bar that hides the content of the window
+    pub fn collapsible(mut self) -> Self {
+        self.collapsible = true;
+        self
+    }
+
+    fn title_text(&self, style: &Style) -> Option<Text> {
+        self.title.as_ref().map(|title| Text {
+            text: title.clone(),
+            font: style.font.clone(),
+            size: 16.0,
+            wrap: TextWrap::NoWrap,
+            color: Color::black(),
+            border: None,
+            padding: Rect::zero(),
+        })
+    }
+
+    fn title_height(&self, style: &Style) -> f32 {
+        self.title_text(style).map_or(0.0, |text| {
+            let margin = style.window_title.margin();
+            let height = text.measure(None).height()
+                .max(style.window_close.size.height())
+                .max(style.window_collapse.size.height());
+            height + margin.top + margin.bottom
+        })
+    }
+
+    // The title bar of the window, inside the frame
+    fn title_rect(&self, style: &Style, rect: Rect) -> Option<Rect> {
+        if self.title.is_some() {
+            let inner = style.window.content_rect(rect);
+            Some(Rect { bottom: inner.top + self.title_height(style), ..inner })
+        } else {
+            None
+        }
+    }
+
+    fn button_rect(&self, style: &Style, title: Rect, button: WindowButton) -> Option<Rect> {
+        let content = title.after_padding(style.window_title.margin());
+        let image = match button {
+            WindowButton::Close if self.closable => &style.window_close,
+            WindowButton::Collapse if self.collapsible => &style.window_collapse,
+            _ => return None,
+        };
+        let size = image.size;
+        let top = ((content.top + content.bottom - size.height()) * 0.5).floor();
+        let left = match button {
+            WindowButton::Close => content.right - size.width(),
+            WindowButton::Collapse => content.left,
+        };
+        Some(Rect::from_xywh(left, top, size.width(), size.height()))
+    }
+
+    fn collapsed_height(&self, style: &Style) -> f32 {
+        let margin = style.window.margin();
+        margin.top + margin.bottom + self.title_height(style)
+    }
+
+    // Top left corner of a window of this size in the viewport
+    fn placement_origin(&self, viewport: Rect, size: Rect) -> (f32, f32) {
+        match self.placement {
+            WindowPlacement::Position(x, y) => (x, y),
+            WindowPlacement::Center => (
+                ((viewport.left + viewport.right - size.width()) * 0.5).floor(),
+                ((viewport.top + viewport.bottom - size.height()) * 0.5).floor()
+            ),
+            WindowPlacement::TopLeft => (viewport.left, viewport.top),
+            WindowPlacement::TopRight => (viewport.right - size.width(), viewport.top),
+            WindowPlacement::BottomLeft => (viewport.left, viewport.bottom - size.height()),
+            WindowPlacement::BottomRight => (viewport.right - size.width(), viewport.bottom - size.height()),
         }
     }
 }
 
 impl WidgetBase for Window {
     fn create(&mut self, id: dag::Id, world: &mut Ui, style: &Style) {
+        // the content starts below the title bar
+        let mut margin = style.window.margin();
+        margin.top += self.title_height(style);
+
+        let overflow = cassowary::Variable::new();
+
         let layout = Layout::new()
-            .with_margins(style.window.margin())
+            .with_margins(margin)
+            .with_bottom_overflow(overflow, &mut world.layout_solver)
             .with_constraints(|layout| vec![
                 layout.top |GE(REQUIRED)| world.viewport_top,
                 layout.left |GE(REQUIRED)| world.viewport_left,
                 layout.bottom |LE(REQUIRED)| world.viewport_bottom,
                 layout.right |LE(REQUIRED)| world.viewport_right,
                 layout.width |GE(REQUIRED)| self.min_size.width() as f64,
-                layout.height |GE(REQUIRED)| self.min_size.height() as f64
-            ])
-            .as_editable(&mut world.layout_solver);
+                layout.height + overflow |GE(REQUIRED)| self.min_size.height() as f64
+            ]);
+
+        let layout = match self.max_size {
+            Some(max) => layout.with_constraints(|layout| vec![
+                layout.width |LE(REQUIRED)| max.width() as f64,
+                layout.height |LE(REQUIRED)| max.height() as f64
+            ]),
+            None => layout,
+        };
+
+        // an auto sized window is as small as it's content allows
+        let layout = if self.auto_size {
+            layout
+                .with_constraints(|layout| vec![
+                    layout.width |EQ(WEAK)| 0.0,
+                    layout.height |EQ(WEAK)| 0.0
+                ])
+                .as_movable(&mut world.layout_solver)
+        } else {
+            layout.as_editable(&mut world.layout_solver)
+        };
+
+        let placed = match self.placement {
+            WindowPlacement::Position(x, y) => {
+                world.layout_solver.suggest_value(layout.left, x as f64).ok();
+                world.layout_solver.suggest_value(layout.top, y as f64).ok();
+                true
+            },
+            _ => false,
+        };
 
         world.create_component(id, layout);
         world.create_component(id, WidgetBackground{
@@ -59,26 +317,117 @@ impl WidgetBase for Window {
             hover: Background::Patch(style.window.clone(), 1.0),
             click: Background::Patch(style.window.clone(), 1.0),
         });
+        world.create_component(id, Clipper::new(Rect::zero()).with_updater(|clip, layout| {
+            let layout = layout.unwrap();
+            if let Some(current) = layout.current {
+                // the content of a collapsed window is below it's bottom
+                let mut content = current.after_padding(layout.margin());
+                content.bottom = content.bottom.min(current.bottom).max(content.top);
+                clip.rect = content;
+            }
+        }));
+        world.create_component(id, Drawing::new());
         world.create_component(id, WindowState::Idle);
+        world.create_component(id, WindowChrome {
+            collapsed: false,
+            overflow,
+            expanded: 0.0,
+            hovered: None,
+            closing: false,
+            placed,
+            restore: None,
+            last_click: None,
+        });
     }
 
     fn update(&mut self, id: dag::Id, world: &mut Ui, style: &Style, input: Option<Rect>) -> Option<Rect> {
-        let layout = world.component::<Layout>(id).unwrap();     
+        let layout = world.component::<Layout>(id).unwrap();
+        let current = layout.borrow().current;
+
+        let mut chrome = world.component::<WindowChrome>(id).unwrap();
+        let mut chrome = chrome.borrow_mut();
+        if chrome.closing {
+            chrome.closing = false;
+            self.open = false;
+        }
+
+        let mut drawing = world.component::<Drawing>(id).unwrap();
+        let mut drawing = drawing.borrow_mut();
+        drawing.primitives.clear();
+
+        let rect = match current {
+            Some(rect) => rect,
+            None => return None,
+        };
+
+        if !chrome.placed {
+            let (x, y) = self.placement_origin(world.viewport, rect);
+            let layout = layout.borrow();
+            world.layout_solver.suggest_value(layout.left, x as f64).ok();
+            world.layout_solver.suggest_value(layout.top, y as f64).ok();
+            chrome.placed = true;
+        }
+
+        if let (Some(title), Some(text)) = (self.title_rect(style, rect), self.title_text(style)) {
+            drawing.primitives.push(Primitive::Draw9(style.window_title.clone(), title, Color::white()));
 
-        let content = layout.borrow().current
-            .map(|rect| style.window.content_rect(rect))
-            .unwrap_or(Rect::zero());
+            let mut content = title.after_padding(style.window_title.margin());
+
+            for &button in [WindowButton::Collapse, WindowButton::Close].iter() {
+                if let Some(button_rect) = self.button_rect(style, title, button) {
+                    let image = match button {
+                        WindowButton::Collapse if chrome.collapsed => &style.window_expand,
+                        WindowButton::Collapse => &style.window_collapse,
+                        WindowButton::Close => &style.window_close,
+                    };
+                    let color = if chrome.hovered == Some(button) {
+                        Color { r: 0.2, g: 0.4, b: 0.8, a: 1.0 }
+                    } else {
+                        Color::white()
+                    };
+                    drawing.primitives.push(Primitive::DrawImage(image.clone(), button_rect, color));
+
+                    match button {
+                        WindowButton::Collapse => content.left = button_rect.right + TITLE_SPACING,
+                        WindowButton::Close => content.right = button_rect.left - TITLE_SPACING,
+                    }
+                }
+            }
+
+            drawing.primitives.push(Primitive::PushClip(content));
+            drawing.primitives.push(Primitive::DrawText(text, content));
+            drawing.primitives.push(Primitive::PopClip);
+        }
+
+        if chrome.collapsed {
+            return None;
+        }
+
+        let content = style.window.content_rect(rect);
+        let content = match self.title_rect(style, rect) {
+            Some(title) => Rect { top: title.bottom, ..content },
+            None => content,
+        };
 
         input.and_then(|ir| ir.intersect(&content))
     }
 
     fn event(&mut self, id: dag::Id, world: &mut Ui, style: &Style, context: &mut EventSystemContext) {
+        // the other windows, looked up before the components of this one are borrowed
+        let others: Vec<Rect> = world.rects_with::<WindowChrome>().into_iter()
+            .filter(|&(other, _)| other != id)
+            .map(|(_, rect)| rect)
+            .collect();
+
         let mut layout = world.component::<Layout>(id).unwrap();
         let mut layout = layout.borrow_mut();
 
         let mut state = world.component::<WindowState>(id).unwrap();
         let mut state = state.borrow_mut();
 
+        let mut chrome = world.component::<WindowChrome>(id).unwrap();
+        let mut chrome = chrome.borrow_mut();
+
         if layout.current.is_none() {
             return;
         }
@@ -87,21 +436,37 @@ impl WidgetBase for Window {
 
         let content = layout.current.map(|rect| style.window.content_rect(rect)).unwrap_or(Rect::zero());
 
+        // a window with a title bar can only be dragged by it's title bar
+        let title = self.title_rect(style, rect);
+        let handle = title.unwrap_or(content);
+
+        let button = title.and_then(|title| {
+            [WindowButton::Collapse, WindowButton::Close].iter().cloned().find(|&button| {
+                self.button_rect(style, title, button).map_or(false, |r| context.cursor.inside(&r))
+            })
+        });
+        chrome.hovered = button;
+
         let busy = match state.clone() {
             WindowState::Drag(_, _) |
-            WindowState::Resize(_) => true,
+            WindowState::Resize(_) |
+            WindowState::Pressed(_) => true,
             _ => false,
         };
 
         if context.cursor.inside(&content) {
             if !busy {
-                *state = WindowState::HoverContent(
-                    context.cursor.x - rect.left, 
-                    context.cursor.y - rect.top
-                );
+                *state = if self.draggable && button.is_none() && context.cursor.inside(&handle) {
+                    WindowState::HoverContent(
+                        context.cursor.x - rect.left, 
+                        context.cursor.y - rect.top
+                    )
+                } else {
+                    WindowState::Idle
+                };
             }
         } else if context.cursor.inside(&rect) {
-            if !busy && self.resizable {
+            if !busy && self.resizable && !chrome.collapsed {
                 let hover_left = context.cursor.x < content.left + 4.0;
                 let hover_right = context.cursor.x > content.right - 4.0;
                 let hover_top = context.cursor.y < content.top + 4.0;
@@ -133,12 +498,74 @@ impl WidgetBase for Window {
 
         *state = match state.clone() {
             WindowState::Idle => {
-                WindowState::Idle
+                match (button, context.event) {
+                    (Some(button), Event::Press(Key::LeftMouseButton, _)) => {
+                        context.capture = Capture::CaptureFocus(MouseStyle::ArrowClicking);
+                        WindowState::Pressed(button)
+                    },
+                    _ => WindowState::Idle,
+                }
+            },
+            WindowState::Pressed(pressed) => {
+                context.capture = Capture::CaptureFocus(MouseStyle::ArrowClicking);
+
+                if let Event::Release(Key::LeftMouseButton, _) = context.event {
+                    if button == Some(pressed) {
+                        match pressed {
+                            WindowButton::Close => {
+                                chrome.closing = true;
+                            },
+                            WindowButton::Collapse => {
+                                // the content keeps it's size and moves below the window
+                                let s = &mut world.layout_solver;
+                                let (height, overflow) = if chrome.collapsed {
+                                    (chrome.expanded, 0.0)
+                                } else {
+                                    chrome.expanded = rect.height();
+                                    let height = self.collapsed_height(style);
+                                    (height, chrome.expanded - height)
+                                };
+                                chrome.collapsed = !chrome.collapsed;
+                                if !self.auto_size {
+                                    s.suggest_value(layout.height, height as f64).ok();
+                                }
+                                s.suggest_value(chrome.overflow, overflow as f64).ok();
+                            },
+                        }
+                    }
+                    WindowState::Idle
+                } else {
+                    WindowState::Pressed(pressed)
+                }
             },
             WindowState::HoverContent(x, y) => {
                 if let Event::Press(Key::LeftMouseButton, _) = context.event {
-                    context.capture = Capture::CaptureFocus(MouseStyle::Arrow);
-                    WindowState::Drag(x, y)
+                    let now = Instant::now();
+                    let double_click = chrome.last_click.map_or(false, |time| {
+                        now.duration_since(time) < Duration::from_millis(DOUBLE_CLICK_TIME)
+                    });
+
+                    // double clicking the title bar maximizes or restores the window
+                    if double_click && title.is_some() && self.resizable && !chrome.collapsed {
+                        chrome.last_click = None;
+                        let target = match chrome.restore.take() {
+                            Some(restore) => restore,
+                            None => {
+                                chrome.restore = Some(rect);
+                                world.viewport
+                            },
+                        };
+                        let s = &mut world.layout_solver;
+                        s.suggest_value(layout.left, target.left as f64).ok();
+                        s.suggest_value(layout.top, target.top as f64).ok();
+                        s.suggest_value(layout.width, target.width() as f64).ok();
+                        s.suggest_value(layout.height, target.height() as f64).ok();
+                        WindowState::HoverContent(x, y)
+                    } else {
+                        chrome.last_click = Some(now);
+                        context.capture = Capture::CaptureFocus(MouseStyle::Arrow);
+                        WindowState::Drag(x, y)
+                    }
                 } else {
                     WindowState::HoverContent(x, y)
                 }
@@ -151,18 +578,50 @@ impl WidgetBase for Window {
                     WindowState::HoverFrame(anchor)
                 }
             },
-            WindowState::Drag(x, y) => {
+            WindowState::Drag(mut x, y) => {
                 context.capture = Capture::CaptureFocus(MouseStyle::Arrow);
 
+                let mut size = rect;
+
+                // a maximized window gets it's previous size back when it's dragged away, while
+                //  the cursor stays on the same relative spot of the title bar
+                let moving = context.cursor.x - x != rect.left || context.cursor.y - y != rect.top;
+                if moving && self.draggable {
+                    if let Some(restore) = chrome.restore.take() {
+                        x = (x * restore.width() / rect.width()).floor();
+                        size = restore;
+                    }
+                }
+
                 if self.draggable {
+                    let moved = Rect::from_xywh(context.cursor.x - x, context.cursor.y - y, size.width(), size.height());
+                    let (xs, ys) = snap_targets(world.viewport, &others, moved);
+                    let moved = moved.translate(
+                        snap(&[moved.left, moved.right], &xs),
+                        snap(&[moved.top, moved.bottom], &ys)
+                    );
+
                     let s = &mut world.layout_solver;
-                    s.suggest_value(layout.left, (context.cursor.x - x) as f64).ok();
-                    s.suggest_value(layout.top, (context.cursor.y - y) as f64).ok();
-                    s.suggest_value(layout.width, rect.width() as f64).ok();
-                    s.suggest_value(layout.height, rect.height() as f64).ok();
+                    s.suggest_value(layout.left, moved.left as f64).ok();
+                    s.suggest_value(layout.top, moved.top as f64).ok();
+                    if !self.auto_size {
+                        s.suggest_value(layout.width, moved.width() as f64).ok();
+                        s.suggest_value(layout.height, moved.height() as f64).ok();
+                    }
                 }
 
                 if let Event::Release(Key::LeftMouseButton, _) = context.event {
+                    // dropping the window on the top edge of the viewport maximizes it
+                    let maximize = self.draggable && self.resizable && !chrome.collapsed &&
+                        context.cursor.y < world.viewport.top + SNAP_DISTANCE;
+                    if maximize {
+                        chrome.restore = Some(Rect::from_xywh(context.cursor.x - x, world.viewport.top, size.width(), size.height()));
+                        let s = &mut world.layout_solver;
+                        s.suggest_value(layout.left, world.viewport.left as f64).ok();
+                        s.suggest_value(layout.top, world.viewport.top as f64).ok();
+                        s.suggest_value(layout.width, world.viewport.width() as f64).ok();
+                        s.suggest_value(layout.height, world.viewport.height() as f64).ok();
+                    }
                     WindowState::Idle
                 } else {
                     WindowState::Drag(x, y)
@@ -173,6 +632,16 @@ impl WidgetBase for Window {
 
                 let min_w = self.min_size.width();
                 let min_h = self.min_size.height();
+                let max_w = self.max_size.map_or(::std::f32::INFINITY, |max| max.width());
+                let max_h = self.max_size.map_or(::std::f32::INFINITY, |max| max.height());
+
+                // the dragged edges follow the cursor, so snapping the cursor snaps the edges
+                let (xs, ys) = snap_targets(world.viewport, &others, rect);
+                let cursor_x = context.cursor.x + snap(&[context.cursor.x], &xs);
+                let cursor_y = context.cursor.y + snap(&[context.cursor.y], &ys);
+
+                // a resized window is no longer maximized
+                chrome.restore = None;
 
                 let s = &mut world.layout_solver;
 
@@ -180,32 +649,32 @@ impl WidgetBase for Window {
 
                 match anchor {
                     MouseStyle::ResizeN => {
-                        new_rect.top = context.cursor.y.min(rect.bottom - min_h);
+                        new_rect.top = cursor_y.min(rect.bottom - min_h).max(rect.bottom - max_h);
                     },
                     MouseStyle::ResizeS => {
-                        new_rect.bottom = context.cursor.y.max(rect.top + min_h);
+                        new_rect.bottom = cursor_y.max(rect.top + min_h).min(rect.top + max_h);
                     },
                     MouseStyle::ResizeW => {
-                        new_rect.left = context.cursor.x.min(rect.right - min_w);
+                        new_rect.left = cursor_x.min(rect.right - min_w).max(rect.right - max_w);
                     },
                     MouseStyle::ResizeE => {
-                        new_rect.right = context.cursor.x.max(rect.left + min_w);
+                        new_rect.right = cursor_x.max(rect.left + min_w).min(rect.left + max_w);
                     },
                     MouseStyle::ResizeNw => {
-                        new_rect.top =  context.cursor.y.min(rect.bottom - min_h);
-                        new_rect.left = context.cursor.x.min(rect.right - min_w);
+                        new_rect.top =  cursor_y.min(rect.bottom - min_h).max(rect.bottom - max_h);
+                        new_rect.left = cursor_x.min(rect.right - min_w).max(rect.right - max_w);
                     },
                     MouseStyle::ResizeNe => {
-                        new_rect.top = context.cursor.y.min(rect.bottom - min_h);
-                        new_rect.right = context.cursor.x.max(rect.left + min_w);
+                        new_rect.top = cursor_y.min(rect.bottom - min_h).max(rect.bottom - max_h);
+                        new_rect.right = cursor_x.max(rect.left + min_w).min(rect.left + max_w);
                     },
                     MouseStyle::ResizeSw => {
-                        new_rect.bottom = context.cursor.y.max(rect.top + min_h);
-                        new_rect.left = context.cursor.x.min(rect.right - min_w);
+                        new_rect.bottom = cursor_y.max(rect.top + min_h).min(rect.top + max_h);
+                        new_rect.left = cursor_x.min(rect.right - min_w).max(rect.right - max_w);
                     },
                     MouseStyle::ResizeSe => {
-                        new_rect.bottom = context.cursor.y.max(rect.top + min_h);
-                        new_rect.right = context.cursor.x.max(rect.left + min_w);
+                        new_rect.bottom = cursor_y.max(rect.top + min_h).min(rect.top + max_h);
+                        new_rect.right = cursor_x.max(rect.left + min_w).min(rect.left + max_w);
                     },
                     _ => {
                         unreachable!();